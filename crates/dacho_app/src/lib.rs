@@ -1,8 +1,9 @@
 // dacho/crates/dacho_app/src/lib.rs
 
-use std::time::Instant;
+use std::{error, fmt, time::Instant};
 
 use winit::application::ApplicationHandler;
+use winit::error::{EventLoopError, OsError};
 use winit::event::{KeyEvent, MouseButton, MouseScrollDelta, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::window::WindowId;
@@ -10,7 +11,7 @@ use winit::window::WindowId;
 #[cfg(feature = "gilrs")]
 use gilrs::{ev::EventType, {Event, Gilrs}};
 
-use dacho_renderer::{Meshes, Renderer, Vulkan};
+use dacho_renderer::{mesh::Instance, Meshes, Renderer, RendererConfig, Vulkan, VulkanConfig};
 use dacho_window::{winit, Window};
 
 pub use dacho_renderer;
@@ -20,10 +21,10 @@ pub use dacho_window;
 pub use gilrs;
 
 
-pub trait GameTrait: Default {
+pub trait GameTrait<I: Instance = [f32; 2]>: Default {
     // execution flow -------------------------------------------------------
-    fn    setup(&mut self) -> Meshes;
-    fn   update(&mut self, _meshes: &mut Renderer, _delta_time: f32) {}
+    fn    setup(&mut self) -> Meshes<I>;
+    fn   update(&mut self, _meshes: &mut Renderer<I>, _delta_time: f32) {}
     fn  exiting(&mut self)                                           {}
 
     // window events --------------------------------------------------------
@@ -44,60 +45,138 @@ pub trait GameTrait: Default {
     fn  gamepad(&mut self, _id:     usize,       _event:        EventType) {}
 }
 
+#[expect(clippy::error_impl_error, reason = "meant to be used as `dacho_app::Error`")]
+#[derive(Debug)]
+pub enum Error {
+    EventLoop(EventLoopError),
+    Window(OsError),
+    Renderer(dacho_renderer::Error),
+    #[cfg(feature = "gilrs")]
+    // NOTE: boxed, it is much larger than the other errors
+    Gamepad(Box<gilrs::Error>)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::EventLoop(ref err) => write!(f, "event loop error: {err}"),
+            Self::Window   (ref err) => write!(f, "failed to create the window: {err}"),
+            Self::Renderer (ref err) => write!(f, "{err}"),
+            #[cfg(feature = "gilrs")]
+            Self::Gamepad  (ref err) => write!(f, "failed to initialise gamepad input: {err}")
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Self::EventLoop(ref err) => Some(err),
+            Self::Window   (ref err) => Some(err),
+            Self::Renderer (ref err) => Some(err),
+            #[cfg(feature = "gilrs")]
+            Self::Gamepad  (ref err) => Some(&**err)
+        }
+    }
+}
+
 #[derive(Default)]
-pub struct App<G: GameTrait> {
-    timer:    Option<Instant>,
-    window:   Window,
-    vulkan:   Option<Vulkan>,
-    renderer: Option<Renderer>,
+pub struct App<G: GameTrait<I>, I: Instance = [f32; 2]> {
+    timer:           Option<Instant>,
+    window:          Window,
+    vulkan:          Option<Vulkan>,
+    renderer:        Option<Renderer<I>>,
+    vulkan_config:   VulkanConfig,
+    renderer_config: RendererConfig,
+    // the first one that stopped the event loop
+    error:           Option<Error>,
 
     #[cfg(feature = "gilrs")]
-    gilrs:    Option<Gilrs>,
+    gilrs:           Option<Gilrs>,
 
-    game:     G
+    game:            G
 }
 
-impl<G: GameTrait> App<G> {
-    pub fn run(mut self) {
-        let event_loop = EventLoop::new().unwrap();
+impl<G: GameTrait<I>, I: Instance> App<G, I> {
+    #[must_use]
+    pub fn with_vulkan_config(mut self, vulkan_config: VulkanConfig) -> Self {
+        self.vulkan_config = vulkan_config;
+        self
+    }
 
-        event_loop.set_control_flow(ControlFlow::Poll);
-        event_loop.run_app(&mut self).unwrap();
+    #[must_use]
+    pub const fn with_renderer_config(mut self, renderer_config: RendererConfig) -> Self {
+        self.renderer_config = renderer_config;
+        self
     }
-}
 
-impl<G: GameTrait> ApplicationHandler for App<G> {
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        if self.window.initialised {
-            return;
-        }
+    /// # Errors
+    /// the first error that stopped the app, `GameTrait::exiting` is called before returning it
+    pub fn run(mut self) -> Result<(), Error> {
+        let event_loop = EventLoop::new()
+            .map_err(Error::EventLoop)?;
 
-        self.timer = Some(Instant::now());
+        event_loop.set_control_flow(ControlFlow::Poll);
+        event_loop.run_app(&mut self)
+            .map_err(Error::EventLoop)?;
 
-        #[cfg(feature = "gilrs")]
-        { self.gilrs = Some(Gilrs::new().unwrap()); }
+        self.error.map_or(Ok(()), Err)
+    }
 
-        self.window.initialise(event_loop);
+    fn initialise(&mut self, event_loop: &ActiveEventLoop) -> Result<(), Error> {
+        #[cfg(feature = "gilrs")]
+        { self.gilrs = Some(Gilrs::new().map_err(|err| Error::Gamepad(Box::new(err)))?); }
 
-        let required_extensions = self.window.required_extensions(event_loop);
+        self.window.initialise(event_loop)
+            .map_err(Error::Window)?;
 
-        let vulkan   = Vulkan::new(required_extensions);
+        let vulkan = Vulkan::new(self.window.handle(), &self.vulkan_config)
+            .map_err(Error::Renderer)?;
         let renderer = vulkan.new_renderer(
             self.window.handle(),
             self.window.size.width,
             self.window.size.height,
             self.window.clear_color,
-            self.game.setup()
-        );
+            self.game.setup(),
+            &self.renderer_config
+        ).map_err(Error::Renderer)?;
 
         self.vulkan   = Some(vulkan);
         self.renderer = Some(renderer);
+
+        Ok(())
+    }
+
+    // NOTE: only the first error is kept, the rest are likely caused by it
+    fn fail(&mut self, event_loop: &ActiveEventLoop, error: Error) {
+        if self.error.is_none() {
+            self.game.exiting();
+        }
+
+        self.error.get_or_insert(error);
+        event_loop.exit();
+    }
+}
+
+impl<G: GameTrait<I>, I: Instance> ApplicationHandler for App<G, I> {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.initialised {
+            return;
+        }
+
+        self.timer = Some(Instant::now());
+
+        if let Err(err) = self.initialise(event_loop) {
+            self.fail(event_loop, err);
+        }
     }
 
     #[inline]
     fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
-        let timer    = self.timer   .as_mut().unwrap();
-        let renderer = self.renderer.as_mut().unwrap();
+        // NOTE: still called once after a failed initialisation
+        let (Some(timer), Some(renderer)) = (self.timer.as_mut(), self.renderer.as_mut()) else {
+            return;
+        };
         #[cfg(feature = "gilrs")]
         let gilrs    = self.gilrs   .as_mut().unwrap();
 
@@ -124,20 +203,28 @@ impl<G: GameTrait> ApplicationHandler for App<G> {
                 event_loop.exit();
             },
             WindowEvent::RedrawRequested => {
-                let vulkan   = self.vulkan  .as_ref().unwrap();
-                let renderer = self.renderer.as_mut().unwrap();
+                let (Some(vulkan), Some(renderer)) = (self.vulkan.as_ref(), self.renderer.as_mut()) else {
+                    return;
+                };
 
-                vulkan.render(renderer, || { self.window.pre_present() });
+                if let Err(err) = vulkan.render(renderer, || { self.window.pre_present() }) {
+                    self.fail(event_loop, Error::Renderer(err));
+                }
             },
             WindowEvent::Resized(new_size) => {
                 if !self.window.resized(new_size) {
                     return;
                 }
 
-                let vulkan   = self.vulkan  .as_ref().unwrap();
-                let renderer = self.renderer.as_mut().unwrap();
+                let (Some(vulkan), Some(renderer)) = (self.vulkan.as_ref(), self.renderer.as_mut()) else {
+                    return;
+                };
+
+                if let Err(err) = vulkan.resize(renderer, new_size.width, new_size.height) {
+                    self.fail(event_loop, Error::Renderer(err));
+                    return;
+                }
 
-                vulkan.resize(renderer, new_size.width, new_size.height);
                 self.game.resized(new_size.width, new_size.height);
             },
             WindowEvent::Moved(position) => {
@@ -175,10 +262,15 @@ impl<G: GameTrait> ApplicationHandler for App<G> {
     }
 
     fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
-        let vulkan   = self.vulkan  .take().unwrap();
-        let renderer = self.renderer.take().unwrap();
+        let (Some(vulkan), Some(renderer)) = (self.vulkan.take(), self.renderer.take()) else {
+            return;
+        };
+
+        // NOTE: destroyed either way, even a lost device can destroy what it created
+        if let Err(err) = vulkan.device_wait_idle() {
+            self.error.get_or_insert(Error::Renderer(err));
+        }
 
-        vulkan.device_wait_idle();
         vulkan.destroy_renderer(renderer);
     }
 }
@@ -0,0 +1,406 @@
+// dacho/crates/dacho_renderer/src/allocator.rs
+
+use std::{collections::HashMap, ptr};
+
+use ash::vk;
+
+
+// NOTE: smaller on small heaps (see Allocator::block_size),
+//       and bigger for allocations that do not fit
+const BLOCK_SIZE: u64 = 64 * 1024 * 1024;
+
+// a suballocated range of a block
+#[derive(Clone, Copy)]
+pub(crate) struct Allocation {
+    memory:            vk::DeviceMemory,
+    offset:            u64,
+    size:              u64,
+    memory_type_index: u32,
+    linear:            bool,
+    // null if the memory is not host visible
+    mapped:            *mut u8
+}
+
+impl Allocation {
+    #[must_use]
+    pub(crate) const fn memory(&self) -> vk::DeviceMemory {
+        self.memory
+    }
+
+    #[must_use]
+    pub(crate) const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    // NOTE: stays mapped for as long as the allocation lives
+    #[must_use]
+    pub(crate) const fn mapped(&self) -> *mut u8 {
+        self.mapped
+    }
+}
+
+// one vkAllocateMemory call, handed out in pieces
+struct Block {
+    memory:           vk::DeviceMemory,
+    size:             u64,
+    mapped:           *mut u8,
+    // (offset, size), sorted by offset, and never touching each other
+    free_regions:     Vec<(u64, u64)>,
+    allocation_count: usize
+}
+
+impl Block {
+    // first fit, returns the offset
+    #[must_use]
+    fn suballocate(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        let (i, offset) = self.free_regions
+            .iter()
+            .enumerate()
+            .find_map(|(i, &(region_offset, region_size))| {
+                let offset = region_offset.next_multiple_of(alignment);
+
+                (offset + size <= region_offset + region_size).then_some((i, offset))
+            })?;
+
+        let (region_offset, region_size) = self.free_regions.remove(i);
+
+        let after_offset = offset + size;
+        let after_size   = region_offset + region_size - after_offset;
+        let before_size  = offset - region_offset;
+
+        if after_size != 0 {
+            self.free_regions.insert(i, (after_offset, after_size));
+        }
+        if before_size != 0 {
+            self.free_regions.insert(i, (region_offset, before_size));
+        }
+
+        self.allocation_count += 1;
+
+        Some(offset)
+    }
+
+    // merges the range with the free regions around it
+    fn release(&mut self, offset: u64, size: u64) {
+        let mut i = self.free_regions.partition_point(|&(region_offset, _)| region_offset < offset);
+
+        let mut merged_offset = offset;
+        let mut merged_size   = size;
+
+        if let Some(&(next_offset, next_size)) = self.free_regions.get(i) && offset + size == next_offset {
+            merged_size += next_size;
+            self.free_regions.remove(i);
+        }
+
+        if let Some(previous) = i.checked_sub(1) {
+            let (previous_offset, previous_size) = self.free_regions[previous];
+
+            if previous_offset + previous_size == offset {
+                merged_offset  = previous_offset;
+                merged_size   += previous_size;
+                self.free_regions.remove(previous);
+                i = previous;
+            }
+        }
+
+        self.free_regions.insert(i, (merged_offset, merged_size));
+        self.allocation_count -= 1;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoryStats {
+    // vkAllocateMemory calls that are alive
+    pub block_count:         usize,
+    pub allocation_count:    usize,
+    // bytes in all blocks
+    pub reserved:            u64,
+    // bytes handed out, including alignment padding
+    pub used:                u64,
+    pub free_region_count:   usize,
+    pub largest_free_region: u64
+}
+
+impl MemoryStats {
+    // 0 when all free memory is in one region, approaching 1 as it gets split into small regions
+    #[must_use]
+    pub fn fragmentation(&self) -> f32 {
+        let free = self.reserved - self.used;
+
+        if free == 0 {
+            return 0.0;
+        }
+
+        1.0 - self.largest_free_region as f32 / free as f32
+    }
+}
+
+// NOTE: buffers and optimal tiling images get separate blocks,
+//       so bufferImageGranularity never has to be accounted for
+pub(crate) struct Allocator {
+    memory_properties: vk::PhysicalDeviceMemoryProperties,
+    // per (memory type index, linear)
+    blocks:            HashMap<(u32, bool), Vec<Block>>
+}
+
+impl Allocator {
+    #[must_use]
+    pub(crate) fn new(memory_properties: &vk::PhysicalDeviceMemoryProperties) -> Self {
+        Self {
+            memory_properties: *memory_properties,
+            blocks: HashMap::new()
+        }
+    }
+
+    // `preferred` properties are dropped if no memory type has them on top of the `required` ones,
+    // `linear` is true for buffers and linear tiling images
+    pub(crate) fn allocate(
+        &mut self,
+        device:       &ash::Device,
+        requirements: vk::MemoryRequirements,
+        required:     vk::MemoryPropertyFlags,
+        preferred:    vk::MemoryPropertyFlags,
+        linear:       bool
+    ) -> Result<Allocation, vk::Result> {
+        // NOTE: the closest vulkan error for no suitable memory type
+        let memory_type_index = self.find_memory_type_index(requirements.memory_type_bits, required | preferred)
+            .or_else(|| self.find_memory_type_index(requirements.memory_type_bits, required))
+            .ok_or(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY)?;
+
+        let block_size = self.block_size(memory_type_index).max(requirements.size);
+        let blocks     = self.blocks.entry((memory_type_index, linear)).or_default();
+
+        let existing = blocks
+            .iter_mut()
+            .find_map(|block| {
+                block
+                    .suballocate(requirements.size, requirements.alignment)
+                    .map(|offset| (block.memory, block.mapped, offset))
+            });
+
+        let (memory, block_mapped, offset) = if let Some(suballocation) = existing {
+            suballocation
+        } else {
+            let mut block = allocate_block(device, &self.memory_properties, memory_type_index, block_size)?;
+
+            let offset = block
+                .suballocate(requirements.size, requirements.alignment)
+                .ok_or(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY)?;
+
+            let suballocation = (block.memory, block.mapped, offset);
+            blocks.push(block);
+
+            suballocation
+        };
+
+        let mapped = if block_mapped.is_null() {
+            block_mapped
+        } else {
+            block_mapped.wrapping_add(usize::try_from(offset).map_err(|_overflow| vk::Result::ERROR_MEMORY_MAP_FAILED)?)
+        };
+
+        Ok(Allocation {
+            memory,
+            offset,
+            size: requirements.size,
+            memory_type_index,
+            linear,
+            mapped
+        })
+    }
+
+    // NOTE: empty blocks are given back to vulkan, except the last one of each kind
+    pub(crate) fn free(&mut self, device: &ash::Device, allocation: Allocation) {
+        let Some(blocks) = self.blocks.get_mut(&(allocation.memory_type_index, allocation.linear)) else {
+            return;
+        };
+
+        let Some(i) = blocks.iter().position(|block| block.memory == allocation.memory) else {
+            return;
+        };
+
+        blocks[i].release(allocation.offset, allocation.size);
+
+        if blocks[i].allocation_count == 0 && blocks.len() > 1 {
+            let block = blocks.swap_remove(i);
+
+            // NOTE: implicitly unmapped
+            unsafe { device.free_memory(block.memory, None); }
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn stats(&self) -> MemoryStats {
+        let mut stats = MemoryStats::default();
+
+        for block in self.blocks.values().flatten() {
+            let free = block.free_regions
+                .iter()
+                .map(|&(_, size)| size)
+                .sum::<u64>();
+
+            stats.block_count         += 1;
+            stats.allocation_count    += block.allocation_count;
+            stats.reserved            += block.size;
+            stats.used                += block.size - free;
+            stats.free_region_count   += block.free_regions.len();
+            stats.largest_free_region  = block.free_regions
+                .iter()
+                .map(|&(_, size)| size)
+                .fold(stats.largest_free_region, u64::max);
+        }
+
+        stats
+    }
+
+    // NOTE: everything allocated from it has to be destroyed already
+    pub(crate) fn destroy(&mut self, device: &ash::Device) {
+        for block in self.blocks.drain().flat_map(|(_, blocks)| blocks) {
+            unsafe { device.free_memory(block.memory, None); }
+        }
+    }
+
+    // at most an eighth of the heap, so small heaps (like the host visible part of vram) are not hogged
+    #[must_use]
+    fn block_size(&self, memory_type_index: u32) -> u64 {
+        let heap_index = self.memory_properties.memory_types[memory_type_index as usize].heap_index;
+        let heap_size  = self.memory_properties.memory_heaps[heap_index as usize].size;
+
+        BLOCK_SIZE.min(heap_size / 8)
+    }
+
+    #[must_use]
+    fn find_memory_type_index(&self, memory_type_bits: u32, required_properties: vk::MemoryPropertyFlags) -> Option<u32> {
+        (0..self.memory_properties.memory_type_count).find(|&i| {
+            let contains = self.memory_properties
+                .memory_types[i as usize]
+                .property_flags
+                .contains(required_properties);
+
+            contains && (memory_type_bits & (1 << i)) != 0
+        })
+    }
+}
+
+fn allocate_block(
+    device:            &ash::Device,
+    memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    memory_type_index: u32,
+    size:              u64
+) -> Result<Block, vk::Result> {
+    // NOTE: every buffer can have its device address taken
+    let mut memory_allocate_flags_info = vk::MemoryAllocateFlagsInfo::default()
+        .flags(vk::MemoryAllocateFlags::DEVICE_ADDRESS);
+    let memory_allocate_info = vk::MemoryAllocateInfo::default()
+        .allocation_size(size)
+        .memory_type_index(memory_type_index)
+        .push_next(&mut memory_allocate_flags_info);
+    let memory = unsafe { device.allocate_memory(&memory_allocate_info, None) }?;
+
+    let host_visible = memory_properties
+        .memory_types[memory_type_index as usize]
+        .property_flags
+        .contains(vk::MemoryPropertyFlags::HOST_VISIBLE);
+
+    let mapped = if host_visible {
+        unsafe { device.map_memory(memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty()) }
+            .inspect_err(|_| unsafe { device.free_memory(memory, None); })?
+            .cast::<u8>()
+    } else {
+        ptr::null_mut()
+    };
+
+    Ok(Block {
+        memory,
+        size,
+        mapped,
+        free_regions:     vec![(0, size)],
+        allocation_count: 0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    fn block(size: u64) -> Block {
+        Block {
+            memory:           vk::DeviceMemory::null(),
+            size,
+            mapped:           ptr::null_mut(),
+            free_regions:     vec![(0, size)],
+            allocation_count: 0
+        }
+    }
+
+    #[test]
+    fn aligned_allocation_leaves_a_leading_gap() {
+        let mut block = block(256);
+
+        assert_eq!(block.suballocate(10,  1), Some(0));
+        assert_eq!(block.suballocate(16, 64), Some(64));
+
+        assert_eq!(block.free_regions,     [(10, 54), (80, 176)]);
+        assert_eq!(block.allocation_count, 2);
+
+        // NOTE: first fit, the gap is used again once something fits in it
+        assert_eq!(block.suballocate(8, 8), Some(16));
+        assert_eq!(block.free_regions,      [(10, 6), (24, 40), (80, 176)]);
+    }
+
+    #[test]
+    fn releasing_in_every_order_coalesces() {
+        let orders = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+
+        for order in orders {
+            let mut block = block(100);
+
+            // NOTE: the last region is left free, so merging with it is covered too
+            let allocations = [(10, 1), (20, 16), (30, 1)].map(|(size, alignment)| {
+                (block.suballocate(size, alignment).unwrap(), size)
+            });
+
+            assert_eq!(allocations, [(0, 10), (16, 20), (36, 30)]);
+
+            for i in order {
+                let (offset, size) = allocations[i];
+                block.release(offset, size);
+
+                assert!(block.free_regions.is_sorted(),                                     "{order:?}: {:?}", block.free_regions);
+                assert!(block.free_regions.windows(2).all(|pair| pair[0].0 + pair[0].1 < pair[1].0), "{order:?}: {:?}", block.free_regions);
+            }
+
+            assert_eq!(block.free_regions,     [(0, 100)], "{order:?}");
+            assert_eq!(block.allocation_count, 0,          "{order:?}");
+        }
+    }
+
+    #[test]
+    fn full_block_returns_none() {
+        let mut block = block(64);
+
+        assert_eq!(block.suballocate(64, 1), Some(0));
+        assert_eq!(block.suballocate( 1, 1), None);
+
+        assert!(block.free_regions.is_empty());
+    }
+
+    #[test]
+    fn alignment_past_the_end_returns_none() {
+        let mut block = block(100);
+
+        assert_eq!(block.suballocate(10,  1), Some(0));
+        assert_eq!(block.suballocate(40, 64), None);
+        assert_eq!(block.free_regions,        [(10, 90)]);
+    }
+
+    #[test]
+    fn fragmentation() {
+        let stats = |used, largest_free_region| MemoryStats { reserved: 100, used, largest_free_region, ..MemoryStats::default() };
+
+        assert!(stats(100,  0).fragmentation().abs()         < f32::EPSILON);
+        assert!(stats( 50, 50).fragmentation().abs()         < f32::EPSILON);
+        assert!((stats(50, 25).fragmentation() - 0.5).abs()  < f32::EPSILON);
+    }
+}
@@ -0,0 +1,63 @@
+// dacho/crates/dacho_renderer/src/camera.rs
+
+// NOTE: at zoom 1 the viewport is 2 units tall, and as wide as the aspect ratio allows,
+//       +y points down like in vulkan clip space
+#[derive(Clone, Copy)]
+pub struct Camera2D {
+    pub position:     [f32; 2],
+    pub zoom:         f32,
+    pub rotation:     f32,
+        aspect_ratio: f32
+}
+
+impl Default for Camera2D {
+    fn default() -> Self {
+        Self {
+            position:     [0.0, 0.0],
+            zoom:         1.0,
+            rotation:     0.0,
+            aspect_ratio: 1.0
+        }
+    }
+}
+
+impl Camera2D {
+    // width / height of the viewport, kept up to date by the renderer
+    #[must_use]
+    pub const fn aspect_ratio(&self) -> f32 {
+        self.aspect_ratio
+    }
+
+    #[inline]
+    pub(crate) const fn set_viewport_size(&mut self, width: f32, height: f32) {
+        self.aspect_ratio = width / height;
+    }
+
+    // column-major, world space to clip space
+    #[must_use]
+    pub fn view_projection(&self) -> [[f32; 4]; 4] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let scale_x    = self.zoom / self.aspect_ratio;
+        let scale_y    = self.zoom;
+
+        let [x, y] = self.position;
+
+        [
+            [                     cos * scale_x,                    -sin * scale_y,   0.0, 0.0],
+            [                     sin * scale_x,                     cos * scale_y,   0.0, 0.0],
+            [                               0.0,                               0.0,   1.0, 0.0],
+            [-cos.mul_add(x, sin * y) * scale_x, sin.mul_add(x, -cos * y) * scale_y,   0.0, 1.0]
+        ]
+    }
+}
+
+// column-major, pixels from the top left corner to clip space
+#[must_use]
+pub(crate) fn screen_projection(width: f32, height: f32) -> [[f32; 4]; 4] {
+    [
+        [2.0 / width,          0.0, 0.0, 0.0],
+        [        0.0, 2.0 / height, 0.0, 0.0],
+        [        0.0,          0.0, 1.0, 0.0],
+        [       -1.0,         -1.0, 0.0, 1.0]
+    ]
+}
@@ -0,0 +1,73 @@
+// dacho/crates/dacho_renderer/src/compute.rs
+
+use std::{any, mem, ptr, slice};
+
+use crate::mesh::Mesh;
+use crate::pipeline::ShaderSource;
+
+
+// NOTE: the push constants of a compute pass start with
+//       { uint64_t vertices_pointer; uint64_t indices_pointer; uint64_t instances_pointer; uint32_t offset; uint32_t count; },
+//       and the user data from `Meshes::set_push_constants` follows at this offset,
+//       zeroed until it is set
+pub const PUSH_CONSTANTS_OFFSET: usize = 3 * mem::size_of::<u64>() + 2 * mem::size_of::<u32>();
+
+// the minimum push constant size guaranteed by vulkan, minus the header above
+pub const MAX_PUSH_CONSTANTS_SIZE: usize = 128 - PUSH_CONSTANTS_OFFSET;
+
+/// # Safety
+/// has to be plain old data with a layout matching the push constants in the shader,
+/// which means `#[repr(C)]` with std430 offsets in practice
+pub unsafe trait PushConstants: Copy + 'static {}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Buffer {
+    Vertices,
+    Indices,
+    Instances
+}
+
+#[derive(Clone)]
+pub enum Dispatch {
+    // workgroup counts, `offset` and `count` are 0
+    Workgroups([u32; 3]),
+    // 1D, rounded up to whole workgroups, `offset` is 0 and `count` is the invocation count
+    Invocations(u32),
+    // 1D, one dispatch per chunk of live instances of a mesh,
+    // `offset` is the first instance of the chunk and `count` is how many are live
+    //
+    // NOTE: built with `Dispatch::instances::<M>()`
+    Instances(String)
+}
+
+impl Dispatch {
+    #[must_use]
+    pub fn instances<M: Mesh>() -> Self {
+        Self::Instances(any::type_name::<M>().to_owned())
+    }
+}
+
+#[derive(Clone)]
+pub struct ComputePassDescription {
+    pub shader:   ShaderSource,
+    pub dispatch: Dispatch,
+    // a barrier before the next passes and the vertex shader covers exactly these
+    pub writes:   Vec<Buffer>
+}
+
+// index into the compute passes added with `Meshes::add_compute_pass`, they run in that order
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComputePass(pub(crate) usize);
+
+#[must_use]
+pub(crate) fn push_constant_bytes<P: PushConstants>(value: &P) -> Vec<u8> {
+    assert!(
+        mem::size_of::<P>() <= MAX_PUSH_CONSTANTS_SIZE,
+        "`{}` is {} bytes, but compute pass push constants can only be {MAX_PUSH_CONSTANTS_SIZE} bytes",
+        any::type_name::<P>(),
+        mem::size_of::<P>()
+    );
+
+    unsafe { slice::from_raw_parts(ptr::from_ref(value).cast::<u8>(), mem::size_of::<P>()) }
+        .to_vec()
+}
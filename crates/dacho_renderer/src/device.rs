@@ -0,0 +1,313 @@
+// dacho/crates/dacho_renderer/src/device.rs
+
+use std::{cmp::Reverse, env, fmt};
+
+use ash::{khr, vk};
+
+use crate::Error;
+
+
+// set to a device index or (part of) a device name to force that device,
+// takes precedence over `VulkanConfig::device`
+pub const DEVICE_ENV_VAR: &str = "DACHO_DEVICE";
+
+// with the score and queue families, or why it can not be used
+type Candidate = (vk::PhysicalDevice, DeviceInfo, Result<(u32, QueueFamilies), String>);
+
+#[derive(Clone, Debug)]
+pub enum DeviceSelector {
+    // in `vkEnumeratePhysicalDevices` order, like `DeviceInfo::index`
+    Index(usize),
+    // case insensitive, the first device whose name contains it
+    Name(String)
+}
+
+impl DeviceSelector {
+    // an index if it parses as one, a name otherwise
+    #[must_use]
+    pub fn parse(value: &str) -> Self {
+        value
+            .trim()
+            .parse()
+            .map_or_else(|_| Self::Name(value.trim().to_owned()), Self::Index)
+    }
+
+    #[must_use]
+    fn matches(&self, info: &DeviceInfo) -> bool {
+        match *self {
+            Self::Index(index)    => info.index == index,
+            Self::Name(ref name) => info.name.to_lowercase().contains(&name.to_lowercase())
+        }
+    }
+}
+
+impl fmt::Display for DeviceSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Index(index)    => write!(f, "index {index}"),
+            Self::Name(ref name) => write!(f, "`{name}`")
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DeviceInfo {
+    pub index:          usize,
+    pub name:           String,
+    pub device_type:    vk::PhysicalDeviceType,
+    pub vendor_id:      u32,
+    pub device_id:      u32,
+    // packed, see `vk::api_version_major` and co.
+    pub api_version:    u32,
+    // vendor specific encoding
+    pub driver_version: u32
+}
+
+impl DeviceInfo {
+    #[must_use]
+    fn new(index: usize, properties: &vk::PhysicalDeviceProperties) -> Self {
+        let name = properties
+            .device_name_as_c_str()
+            .map_or_else(|_| String::from("<unnamed>"), |name| name.to_string_lossy().into_owned());
+
+        Self {
+            index,
+            name,
+            device_type:    properties.device_type,
+            vendor_id:      properties.vendor_id,
+            device_id:      properties.device_id,
+            api_version:    properties.api_version,
+            driver_version: properties.driver_version
+        }
+    }
+
+    #[must_use]
+    pub const fn device_type_name(&self) -> &'static str {
+        match self.device_type {
+            vk::PhysicalDeviceType::DISCRETE_GPU   => "discrete gpu",
+            vk::PhysicalDeviceType::INTEGRATED_GPU => "integrated gpu",
+            vk::PhysicalDeviceType::VIRTUAL_GPU    => "virtual gpu",
+            vk::PhysicalDeviceType::CPU            => "cpu",
+            _                                      => "other"
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct QueueFamilies {
+    // supports graphics and compute, compute passes are recorded into the same command buffer
+    pub graphics: u32,
+    // the same as `graphics` when it can present, None without a window
+    pub present:  Option<u32>
+}
+
+impl QueueFamilies {
+    // one queue is created for each
+    #[must_use]
+    pub fn unique(&self) -> Vec<u32> {
+        let mut families = vec![self.graphics];
+
+        families.extend(self.present.filter(|&present| present != self.graphics));
+
+        families
+    }
+}
+
+// the forced device if there is one (see `DEVICE_ENV_VAR`), or the highest scoring one otherwise,
+// `surface` is checked for present support when given
+pub(crate) fn select(
+    instance: &ash::Instance,
+    surface:  Option<(&khr::surface::Instance, vk::SurfaceKHR)>,
+    selector: Option<&DeviceSelector>
+) -> Result<(vk::PhysicalDevice, DeviceInfo, QueueFamilies), Error> {
+    let physical_devices = unsafe { instance.enumerate_physical_devices() }
+        .map_err(Error::Instance)?;
+
+    let candidates = physical_devices
+        .iter()
+        .enumerate()
+        .map(|(i, &physical_device)| {
+            let properties = unsafe { instance.get_physical_device_properties(physical_device) };
+            let info       = DeviceInfo::new(i, &properties);
+            let score      = score(instance, physical_device, &properties, surface);
+
+            (physical_device, info, score)
+        })
+        .collect::<Vec<Candidate>>();
+
+    let env_selector = env::var(DEVICE_ENV_VAR)
+        .ok()
+        .map(|value| DeviceSelector::parse(&value));
+
+    if let Some(forced) = env_selector.as_ref().or(selector) {
+        let &(physical_device, ref info, ref score) = candidates
+            .iter()
+            .find(|candidate| forced.matches(&candidate.1))
+            .ok_or_else(|| Error::NoDevice(format!("no vulkan device matches {forced}, the available devices are:\n{}", describe(&candidates))))?;
+
+        let &(_, queue_families) = score
+            .as_ref()
+            .map_err(|err| Error::NoDevice(format!("`{}` can not be used: {err}", info.name)))?;
+
+        return Ok((physical_device, info.clone(), queue_families));
+    }
+
+    let best = candidates
+        .iter()
+        .filter_map(|&(physical_device, ref info, ref result)| {
+            result
+                .as_ref()
+                .ok()
+                .map(|&(score, queue_families)| (physical_device, info, score, queue_families))
+        })
+        .max_by_key(|&(_, info, score, _)| (score, Reverse(info.index)))
+        .ok_or_else(|| Error::NoDevice(format!("no vulkan device can be used:\n{}", describe(&candidates))))?;
+
+    let (physical_device, info, _, queue_families) = best;
+
+    Ok((physical_device, info.clone(), queue_families))
+}
+
+// higher is better, with the queue families to use, Err is why the device can not be used
+fn score(
+    instance:        &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    properties:      &vk::PhysicalDeviceProperties,
+    surface:         Option<(&khr::surface::Instance, vk::SurfaceKHR)>
+) -> Result<(u32, QueueFamilies), String> {
+    if properties.api_version < vk::API_VERSION_1_3 {
+        return Err(format!(
+            "vulkan {}.{} is supported, 1.3 is needed",
+            vk::api_version_major(properties.api_version),
+            vk::api_version_minor(properties.api_version)
+        ));
+    }
+
+    let missing_features = missing_features(instance, physical_device);
+
+    if !missing_features.is_empty() {
+        return Err(format!("missing features: {}", missing_features.join(", ")));
+    }
+
+    // NOTE: only needed to present, headless devices can be on machines without a display
+    if surface.is_some() {
+        let has_swapchain = unsafe { instance.enumerate_device_extension_properties(physical_device) }
+            .map_err(|err| format!("failed to enumerate extensions: {err}"))?
+            .iter()
+            .any(|extension| extension.extension_name_as_c_str() == Ok(vk::KHR_SWAPCHAIN_NAME));
+
+        if !has_swapchain {
+            return Err(String::from("missing the `VK_KHR_swapchain` extension"));
+        }
+    }
+
+    let queue_families = find_queue_families(instance, physical_device, surface)?;
+
+    let score = match properties.device_type {
+        vk::PhysicalDeviceType::DISCRETE_GPU   => 4,
+        vk::PhysicalDeviceType::INTEGRATED_GPU => 3,
+        vk::PhysicalDeviceType::VIRTUAL_GPU    => 2,
+        vk::PhysicalDeviceType::CPU            => 1,
+        _                                      => 0
+    };
+
+    Ok((score, queue_families))
+}
+
+// prefers a graphics family that can present too, so no sharing between families is needed
+fn find_queue_families(
+    instance:        &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    surface:         Option<(&khr::surface::Instance, vk::SurfaceKHR)>
+) -> Result<QueueFamilies, String> {
+    let families = unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
+
+    let mut can_present = Vec::with_capacity(families.len());
+
+    for i in 0..u32::try_from(families.len()).map_err(|err| err.to_string())? {
+        let supported = match surface {
+            Some((ext_surface, surface_khr)) => unsafe { ext_surface.get_physical_device_surface_support(physical_device, i, surface_khr) }
+                .map_err(|err| format!("failed to query present support: {err}"))?,
+            None => false
+        };
+
+        can_present.push(supported);
+    }
+
+    let graphics_families = (0_u32..)
+        .zip(&families)
+        .filter(|&(_, family)| family.queue_flags.contains(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let graphics = graphics_families
+        .iter()
+        .copied()
+        .find(|&i| can_present[i as usize])
+        .or_else(|| graphics_families.first().copied())
+        .ok_or_else(|| String::from("no queue family supports graphics and compute"))?;
+
+    if surface.is_none() {
+        return Ok(QueueFamilies { graphics, present: None });
+    }
+
+    let present = if can_present[graphics as usize] {
+        graphics
+    } else {
+        (0_u32..)
+            .zip(&can_present)
+            .find_map(|(i, &supported)| supported.then_some(i))
+            .ok_or_else(|| String::from("no queue family can present to the window"))?
+    };
+
+    Ok(QueueFamilies { graphics, present: Some(present) })
+}
+
+// the names of the features that `Vulkan::new` enables, but the device does not support
+#[must_use]
+fn missing_features(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> Vec<&'static str> {
+    let mut vulkan13_features = vk::PhysicalDeviceVulkan13Features::default();
+    let mut vulkan12_features = vk::PhysicalDeviceVulkan12Features::default();
+    let mut vulkan11_features = vk::PhysicalDeviceVulkan11Features::default();
+    let mut features = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut vulkan13_features)
+        .push_next(&mut vulkan12_features)
+        .push_next(&mut vulkan11_features);
+    unsafe { instance.get_physical_device_features2(physical_device, &mut features); }
+
+    let vulkan10_features = features.features;
+
+    [
+        ("logicOp",                                      vulkan10_features.logic_op),
+        ("shaderInt64",                                  vulkan10_features.shader_int64),
+        ("dynamicRendering",                             vulkan13_features.dynamic_rendering),
+        ("synchronization2",                             vulkan13_features.synchronization2),
+        ("bufferDeviceAddress",                          vulkan12_features.buffer_device_address),
+        ("runtimeDescriptorArray",                       vulkan12_features.runtime_descriptor_array),
+        ("descriptorBindingPartiallyBound",              vulkan12_features.descriptor_binding_partially_bound),
+        ("descriptorBindingSampledImageUpdateAfterBind", vulkan12_features.descriptor_binding_sampled_image_update_after_bind),
+        ("shaderSampledImageArrayNonUniformIndexing",    vulkan12_features.shader_sampled_image_array_non_uniform_indexing),
+        ("shaderDrawParameters",                         vulkan11_features.shader_draw_parameters)
+    ]
+        .into_iter()
+        .filter(|&(_, supported)| supported == vk::FALSE)
+        .map(|(name, _)| name)
+        .collect()
+}
+
+// one line per device, for error messages
+#[must_use]
+fn describe(candidates: &[Candidate]) -> String {
+    candidates
+        .iter()
+        .map(|candidate| {
+            let info   = &candidate.1;
+            let status = candidate.2
+                .as_ref()
+                .map_or_else(|err| format!("can not be used: {err}"), |_| String::from("usable"));
+
+            format!("  {}: `{}` ({}), {status}", info.index, info.name, info.device_type_name())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,49 @@
+// dacho/crates/dacho_renderer/src/error.rs
+
+use std::{error, fmt};
+
+use ash::vk;
+
+use raw_window_handle::HandleError;
+
+
+// everything that can fail at runtime, mistakes in using the api still panic
+#[expect(clippy::error_impl_error, reason = "meant to be used as `dacho_renderer::Error`")]
+#[derive(Debug)]
+pub enum Error {
+    // the vulkan library is missing, or is not a vulkan library
+    Loader(ash::LoadingError),
+    // creating the instance, or enumerating its devices
+    Instance(vk::Result),
+    // why no device can be used, for every device
+    NoDevice(String),
+    // creating the device, or anything on it that is not covered below, like a lost device
+    Device(vk::Result),
+    WindowHandle(HandleError),
+    Surface(vk::Result),
+    Swapchain(vk::Result),
+    // a message for the user, like a compile error or a mismatched `Instance` size
+    Shader(String),
+    // creating buffers and images, and allocating and binding their memory
+    Memory(vk::Result)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Loader      (ref err)     => write!(f, "failed to load vulkan: {err}"),
+            Self::Instance    (err)         => write!(f, "vulkan instance error: {err}"),
+            Self::NoDevice    (ref message)
+            | Self::Shader    (ref message) => write!(f, "{message}"),
+            Self::Device      (err)         => write!(f, "vulkan device error: {err}"),
+            Self::WindowHandle(ref err)     => write!(f, "window handle error: {err}"),
+            Self::Surface     (err)         => write!(f, "vulkan surface error: {err}"),
+            Self::Swapchain   (err)         => write!(f, "vulkan swapchain error: {err}"),
+            Self::Memory      (err)         => write!(f, "vulkan memory error: {err}")
+        }
+    }
+}
+
+// NOTE: no sources, ash and raw-window-handle are built without `std`,
+//       so their errors do not implement `Error`
+impl error::Error for Error {}
@@ -0,0 +1,125 @@
+// dacho/crates/dacho_renderer/src/glsl.rs
+
+use std::{env, fs};
+use std::path::{Path, PathBuf};
+
+use glslang::error::GlslangError;
+use glslang::{Compiler, CompilerOptions, ShaderInput, ShaderStage, SpirvVersion, Target, VulkanVersion};
+
+
+// compiles every `*.glsl` file under `directory` into `$OUT_DIR/shaders/{relative path}.spv`,
+// to be embedded with `include_shader!("{relative path}")`
+//
+// meant to be called from a build script, compile errors are reported as cargo errors
+#[expect(clippy::print_stdout, reason = "cargo reads build script instructions from stdout")]
+pub fn compile_shaders(directory: impl AsRef<Path>) {
+    let shaders_dir = directory.as_ref();
+    let out_dir     = env::var_os("OUT_DIR");
+
+    assert!(out_dir.is_some(), "`compile_shaders` is meant to be called from a build script");
+
+    let spv_dir = PathBuf::from(out_dir.unwrap()).join("shaders");
+
+    println!("cargo::rerun-if-changed={}", shaders_dir.display());
+    // NOTE: for `include_shader!` with hot reloading
+    println!("cargo::rustc-env=DACHO_SHADERS_DIR={}", fs::canonicalize(shaders_dir).unwrap().display());
+
+    for path in glsl_files(shaders_dir) {
+        let relative = path.strip_prefix(shaders_dir).unwrap();
+
+        match compile(&path) {
+            Ok(words) => {
+                let spv_path = spv_dir.join(relative).with_added_extension("spv");
+
+                fs::create_dir_all(spv_path.parent().unwrap())
+                    .unwrap();
+                fs::write(&spv_path, words.iter().flat_map(|word| word.to_ne_bytes()).collect::<Vec<_>>())
+                    .unwrap();
+            },
+            Err(err) => {
+                err
+                    .lines()
+                    .for_each(|line| println!("cargo::error={line}"));
+            }
+        }
+    }
+}
+
+/// compiles a GLSL file to SPIR-V for vulkan 1.3,
+/// the stage comes from the last extension before `.glsl`, like `vert.glsl` or `sprite.frag.glsl`
+///
+/// # Errors
+/// the compiler log, with every line prefixed with the path
+pub fn compile(path: &Path) -> Result<Vec<u32>, String> {
+    let name = path.display();
+
+    let stage = stage(path)
+        .ok_or_else(|| format!("{name}: unknown shader stage, expected a name like `vert.glsl`, `frag.glsl` or `comp.glsl`"))?;
+
+    let code = fs::read_to_string(path)
+        .map_err(|err| format!("{name}: failed to read: {err}"))?;
+
+    let compiler = Compiler::acquire()
+        .ok_or_else(|| format!("{name}: failed to initialise glslang"))?;
+
+    let options = CompilerOptions {
+        target: Target::Vulkan { version: VulkanVersion::Vulkan1_3, spirv_version: SpirvVersion::SPIRV1_6 },
+        ..Default::default()
+    };
+
+    let source = glslang::ShaderSource::from(code);
+    let input  = ShaderInput::new(&source, stage, &options, None::<&[(&str, Option<&str>)]>, None)
+        .map_err(|err| format!("{name}: {err}"))?;
+
+    compiler
+        .create_shader(input)
+        .and_then(|shader| shader.compile())
+        .map_err(|err| {
+            let log = match err {
+                GlslangError::ParseError(log) | GlslangError::LinkError(log) => log.log,
+                _                                                            => err.to_string()
+            };
+
+            log
+                .lines()
+                .map(str::trim_end)
+                .filter(|line| !line.is_empty())
+                .map(|line| format!("{name}: {line}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+}
+
+#[must_use]
+fn stage(path: &Path) -> Option<ShaderStage> {
+    let file_name = path.file_name()?.to_str()?;
+    let stem      = file_name.strip_suffix(".glsl")?;
+    let extension = stem.rsplit('.').next()?;
+
+    match extension {
+        "vert" => Some(ShaderStage::Vertex),
+        "frag" => Some(ShaderStage::Fragment),
+        "comp" => Some(ShaderStage::Compute),
+        _      => None
+    }
+}
+
+#[must_use]
+fn glsl_files(directory: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.is_dir() {
+            files.extend(glsl_files(&path));
+            continue;
+        }
+
+        if path.extension().is_some_and(|extension| extension == "glsl") {
+            files.push(path);
+        }
+    }
+
+    files
+}
@@ -0,0 +1,51 @@
+// dacho/crates/dacho_renderer/src/hot_reload.rs
+
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::pipeline::ShaderSource;
+
+
+// how often the shader files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// when the shader files of every created pipeline were last modified
+pub struct ShaderWatcher {
+        last_poll: Instant,
+    pub compute:   Vec<Option<SystemTime>>,
+    pub graphics:  Vec<Option<SystemTime>>
+}
+
+impl ShaderWatcher {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            last_poll: Instant::now(),
+            compute:   Vec::new(),
+            graphics:  Vec::new()
+        }
+    }
+
+    // true at most once per `POLL_INTERVAL`, so the files are not checked every frame
+    #[must_use]
+    pub fn should_poll(&mut self) -> bool {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+
+        self.last_poll = Instant::now();
+
+        true
+    }
+}
+
+// the latest modification time of the files behind `sources`,
+// None if none of them are files
+#[must_use]
+pub fn last_modified(sources: &[&ShaderSource]) -> Option<SystemTime> {
+    sources
+        .iter()
+        .filter_map(|source| source.file())
+        .filter_map(|path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+        .max()
+}
@@ -6,28 +6,69 @@
     reason = "most of vulkan is unsafe"
 )]
 
+pub mod allocator;
+pub mod camera;
+pub mod compute;
+pub mod device;
+mod error;
+#[cfg(feature = "glsl")]
+pub mod glsl;
+#[cfg(feature = "hot_reload")]
+mod hot_reload;
 pub mod mesh;
-
-use std::{any, ffi, fs, iter, mem, ptr, slice};
-use std::{cell::Cell, collections::HashMap, rc::Rc};
-
-use ash::{khr, vk};
-
-use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
-
-use mesh::{Mesh, VERTEX_SIZE, INDEX_SIZE, INSTANCE_SIZE};
+pub mod pipeline;
+pub mod pipeline_cache;
+pub mod profiler;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+pub mod swapchain;
+#[cfg(feature = "text")]
+pub mod text;
+pub mod texture;
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use std::{any, ffi, iter, mem, ptr, slice};
+use std::{cell::{Cell, RefCell}, collections::HashMap, marker::PhantomData, path::PathBuf, rc::Rc};
+#[cfg(feature = "png")]
+use std::path::Path;
+
+use ash::{ext, khr, vk};
+
+use raw_window_handle::{HasDisplayHandle, HasWindowHandle, RawDisplayHandle, RawWindowHandle};
+
+use allocator::{Allocation, Allocator, MemoryStats};
+use camera::Camera2D;
+use compute::{Buffer, ComputePass, ComputePassDescription, Dispatch, PushConstants};
+use device::{DeviceInfo, DeviceSelector, QueueFamilies};
+#[cfg(feature = "hot_reload")]
+use hot_reload::ShaderWatcher;
+use mesh::{Instance, Mesh, INDEX_SIZE};
+use pipeline::{Pipeline, PipelineDescription, ShaderSource, Space};
+use pipeline_cache::PipelineCache;
+use profiler::{GpuScope, GpuTiming, Timestamps};
+use swapchain::{ColorSpace, PresentMode, SRGB_SURFACE_FORMAT};
+#[cfg(feature = "text")]
+use text::{Font, Glyph, TextStyle};
+use texture::{Texture, TextureData, MAX_TEXTURES};
+#[cfg(feature = "validation")]
+use validation::DebugMessenger;
 
 pub use ash;
+pub use error::Error;
 
 
-const SWAPCHAIN_FORMAT: vk::Format = vk::Format::R8G8B8A8_SRGB;
-
 const PUSH_CONSTANTS_LEN: usize = {
     3 * mem::size_of::<u64>()
     +
     mem::size_of::<u32>()
 };
 
+// NOTE: the view projection matrix comes after the pointers and the index offset,
+//       aligned to 16 bytes for the `mat4`
+const VIEW_PROJECTION_OFFSET:      usize = PUSH_CONSTANTS_LEN.next_multiple_of(16);
+const GRAPHICS_PUSH_CONSTANTS_LEN: usize = VIEW_PROJECTION_OFFSET + mem::size_of::<[[f32; 4]; 4]>();
+
 type SwapchainAndEverythingRelated = (
     vk::Extent2D,
     vk::ImageSubresourceRange,
@@ -35,50 +76,99 @@ type SwapchainAndEverythingRelated = (
     Vec<vk::Image>,
     Vec<vk::ImageView>,
     [vk::Viewport; 1],
-    [vk::Rect2D;   1],
-    u32
+    [vk::Rect2D;   1]
+);
+
+// image ready semaphores and in flight fences per frame in flight,
+// and render finished semaphores per swapchain image
+type SyncObjects = (Vec<vk::Semaphore>, Vec<vk::Semaphore>, Vec<vk::Fence>);
+
+// vertices and indices with their device addresses, and instances with where they are mapped
+type MeshBuffers<I> = (((vk::Buffer, Allocation), u64), ((vk::Buffer, Allocation), u64), ((vk::Buffer, Allocation), *mut I));
+
+// sync objects, command objects, texture descriptors, pipeline layouts and mesh buffers
+type RendererObjects<I> = (
+    SyncObjects,
+    (vk::CommandPool, Vec<vk::CommandBuffer>, Timestamps),
+    (vk::DescriptorSetLayout, vk::DescriptorPool, vk::DescriptorSet, vk::Sampler),
+    (vk::PipelineLayout, vk::PipelineLayout),
+    MeshBuffers<I>
 );
 
+// NOTE: the offscreen image stands in as the only swapchain image
+struct Offscreen {
+    image:           vk::Image,
+    image_memory:    Allocation,
+    readback:        (vk::Buffer, Allocation),
+    mapped_readback: *const u8
+}
+
 struct InstanceData {
     chunk_offset: usize,
-    count:        usize
+    count:        usize,
+    handles:      Vec<Rc<Cell<usize>>>
 }
 
-#[derive(Default)]
 struct MeshData {
+    pipeline:                Pipeline,
     instance_count_estimate: usize,
+    vertex_stride:           usize,
     vertex_offset:           usize,
     index_offset:            usize,
     index_count:             usize
 }
 
-pub struct InstanceHandle(Rc<Cell<usize>>);
+pub struct InstanceHandle<I: Instance>(Rc<Cell<usize>>, PhantomData<I>);
 
-// TODO: custom Default impl
-#[derive(Default)]
-pub struct Meshes {
+pub struct Meshes<I: Instance = [f32; 2]> {
     registered:                    HashMap<String, MeshData>,
+    // keys of `registered`, in the order they are drawn
+    draw_order:                    Vec<String>,
     instance_datas_per_mesh:       HashMap<String, Vec<InstanceData>>,
-    current_vertex_offset:         usize,
     current_index_offset:          usize,
     current_instance_chunk_offset: usize,
-    vertices:                      Vec<f32>,
+    vertices:                      Vec<u8>,
     indices:                       Vec<u32>,
-    instances:                     Vec<f32>
+    instances:                     Vec<I>,
+    textures:                      Vec<TextureData>,
+    pipelines:                     Vec<PipelineDescription>,
+    compute_passes:                Vec<ComputePassDescription>,
+    compute_push_constants:        Vec<Vec<u8>>
+}
+
+impl<I: Instance> Default for Meshes<I> {
+    fn default() -> Self {
+        Self {
+            registered:                    HashMap::new(),
+            draw_order:                    Vec::new(),
+            instance_datas_per_mesh:       HashMap::new(),
+            current_index_offset:          0,
+            current_instance_chunk_offset: 0,
+            vertices:                      Vec::new(),
+            indices:                       Vec::new(),
+            instances:                     Vec::new(),
+            textures:                      vec![TextureData { width: 1, height: 1, pixels: vec![255; 4] }],
+            pipelines:                     Vec::new(),
+            compute_passes:                Vec::new(),
+            compute_push_constants:        Vec::new()
+        }
+    }
 }
 
 pub struct MeshesCapacities {
     pub different_meshes_count: usize,
+    // NOTE: in bytes, because every mesh can have its own vertex type
     pub vertex_buffer_size:     usize,
     pub index_buffer_size:      usize,
     pub instance_buffer_size:   usize
 }
 
-impl Meshes {
+impl<I: Instance> Meshes<I> {
     #[must_use]
     pub fn with_capacities(caps: &MeshesCapacities) -> Self {
         Self {
             registered: HashMap::with_capacity(caps.different_meshes_count),
+            draw_order:     Vec::with_capacity(caps.different_meshes_count),
             vertices:       Vec::with_capacity(caps.    vertex_buffer_size),
             indices:        Vec::with_capacity(caps.     index_buffer_size),
             instances:      Vec::with_capacity(caps.  instance_buffer_size),
@@ -86,94 +176,223 @@ impl Meshes {
         }
     }
 
-    pub fn register<M: Mesh>(&mut self, instance_count_estimate: usize) {
+    // NOTE: meshes are drawn in the order they are registered,
+    //       so blended ones, like `Space::Screen` text, should be registered last
+    pub fn register<M: Mesh>(&mut self, pipeline: Pipeline, instance_count_estimate: usize) {
         let key = any::type_name::<M>().to_owned();
 
-        assert!(!self.registered.contains_key(&key), "`{key}` is already registered!");
+        assert!(!self.registered.contains_key(&key),   "`{key}` is already registered!");
+        assert!(instance_count_estimate != 0,          "`{key}` needs a non-zero instance count estimate!");
+        assert!(pipeline.0 < self.pipelines.len(),     "`{key}` is registered with a pipeline that was not added!");
+
+        let vertex_stride = mem::size_of::<M::Vertex>();
+
+        assert!(vertex_stride != 0, "`{key}` has a zero sized vertex type!");
+
+        let    vertices = M::vertices().into_iter().collect::<Vec<_>>();
+        let     indices = M:: indices().into_iter().collect::<Vec<_>>();
+        let vertex_size = mem::size_of_val(vertices.as_slice());
+        let index_count = indices.len() * INDEX_SIZE;
 
-        let     vertices = M::vertices().into_iter().collect::<Vec<_>>();
-        let      indices = M:: indices().into_iter().collect::<Vec<_>>();
-        let vertex_count = vertices.len() * VERTEX_SIZE;
-        let  index_count =  indices.len() *  INDEX_SIZE;
+        // NOTE: vertices of different types share one buffer, so each mesh is padded
+        //       to start at a multiple of its own stride, to be indexable with gl_BaseVertex
+        let vertex_byte_offset = self.vertices.len().next_multiple_of(vertex_stride);
 
         let mesh_data = MeshData {
+            pipeline,
             instance_count_estimate,
-            vertex_offset: self.current_vertex_offset,
+            vertex_stride,
+            vertex_offset: vertex_byte_offset / vertex_stride,
             index_offset:  self.current_index_offset,
             index_count
         };
 
+        self.vertices.resize(vertex_byte_offset, 0);
         self.vertices.extend(unsafe {
-            slice::from_raw_parts(vertices.as_ptr().cast::<f32>(), vertex_count)
+            slice::from_raw_parts(vertices.as_ptr().cast::<u8>(), vertex_size)
         });
         self.indices .extend(unsafe {
-            slice::from_raw_parts( indices.as_ptr().cast::<u32>(),  index_count)
+            slice::from_raw_parts( indices.as_ptr().cast::<u32>(), index_count)
         });
 
+        self.draw_order.push(key.clone());
         self.registered.insert(key, mesh_data);
-        self.current_vertex_offset += vertex_count;
-        self.current_index_offset  +=  index_count;
+        self.current_index_offset += index_count;
     }
 
     #[inline]
-    pub fn add_instance<M: Mesh>(&mut self, value: [f32; INSTANCE_SIZE]) -> InstanceHandle {
+    pub fn add_instance<M: Mesh>(&mut self, value: I) -> InstanceHandle<I> {
         let key = any::type_name::<M>().to_owned();
 
-        let mesh_data = self.registered.get(&key)
-            .unwrap_or_else(|| panic!("`{key}` has not yet been registered!"));
+        assert!(self.registered.contains_key(&key), "`{key}` has not yet been registered!");
 
-        let estimated_size = mesh_data.instance_count_estimate * INSTANCE_SIZE;
+        let chunk_capacity = self.registered[&key].instance_count_estimate;
+        let instance_datas = self.instance_datas_per_mesh.entry(key).or_default();
 
-        let i = {
-            if let Some(instance_datas) = self.instance_datas_per_mesh.get_mut(&key) {
-                let instance_data = instance_datas.last_mut().unwrap();
+        // NOTE: instances of M are kept packed (see Self::remove_instance),
+        //       so only the first chunk that is not full can be written into
+        let chunk_index = if let Some(chunk_index) = instance_datas
+            .iter()
+            .position(|instance_data| instance_data.count < chunk_capacity)
+        {
+            chunk_index
+        } else {
+            // another chunk for M
 
-                if instance_data.count == mesh_data.instance_count_estimate {
-                    // another chunk for M
+            instance_datas.push(InstanceData {
+                chunk_offset: self.current_instance_chunk_offset,
+                count:        0,
+                handles:      Vec::with_capacity(chunk_capacity)
+            });
 
-                    let new_chunk = InstanceData {
-                        chunk_offset: self.current_instance_chunk_offset,
-                        count:        1
-                    };
-                    let i = new_chunk.chunk_offset;
+            self.instances.resize(self.instances.len() + chunk_capacity, I::default());
 
-                    self.instances.resize(self.instances.len() + estimated_size, 0.0);
-                    instance_datas.push(new_chunk);
+            self.current_instance_chunk_offset += chunk_capacity;
 
-                    self.current_instance_chunk_offset += estimated_size;
+            instance_datas.len() - 1
+        };
 
-                    i
-                } else {
-                    // last chunk for M
+        let instance_data = &mut instance_datas[chunk_index];
+        let i             = instance_data.chunk_offset + instance_data.count;
+        let offset        = Rc::new(Cell::new(i));
 
-                    let i = instance_data.chunk_offset + (instance_data.count * INSTANCE_SIZE);
+        instance_data.count += 1;
+        instance_data.handles.push(Rc::clone(&offset));
 
-                    instance_data.count += 1;
+        self.instances[i] = value;
 
-                    i
-                }
-            } else {
-                // first chunk for M
+        InstanceHandle(offset, PhantomData)
+    }
 
-                let instance_data = InstanceData {
-                    chunk_offset: self.current_instance_chunk_offset,
-                    count:        1
-                };
-                let i = instance_data.chunk_offset;
+    // NOTE: created by the renderer at the start of the next frame
+    pub fn add_pipeline(&mut self, description: PipelineDescription) -> Pipeline {
+        self.pipelines.push(description);
 
-                self.instances.resize(self.instances.len() + estimated_size, 0.0);
+        Pipeline(self.pipelines.len() - 1)
+    }
 
-                self.instance_datas_per_mesh.insert(key, vec![instance_data]);
+    // NOTE: created by the renderer at the start of the next frame,
+    //       and dispatched before drawing, in the order they were added
+    pub fn add_compute_pass(&mut self, description: ComputePassDescription) -> ComputePass {
+        self.compute_passes.push(description);
+        self.compute_push_constants.push(Vec::new());
 
-                self.current_instance_chunk_offset += estimated_size;
+        ComputePass(self.compute_passes.len() - 1)
+    }
 
-                i
-            }
-        };
+    // NOTE: kept for every following dispatch, until set again
+    pub fn set_push_constants<P: PushConstants>(&mut self, pass: ComputePass, value: &P) {
+        assert!(pass.0 < self.compute_passes.len(), "push constants are set for a compute pass that was not added!");
+
+        self.compute_push_constants[pass.0] = compute::push_constant_bytes(value);
+    }
+
+    // NOTE: `pixels` are tightly packed RGBA8 rows in sRGB
+    pub fn add_texture(&mut self, width: u32, height: u32, pixels: Vec<u8>) -> Texture {
+        assert!(width != 0 && height != 0,                         "textures can not be empty!");
+        assert!(pixels.len() == width as usize * height as usize * 4, "texture pixels are not {width}x{height} RGBA8!");
+        assert!(self.textures.len() < MAX_TEXTURES,                 "too many textures, the limit is {MAX_TEXTURES}!");
 
-        self.instances.splice(i..i + INSTANCE_SIZE, value);
+        let texture = Texture(u32::try_from(self.textures.len()).unwrap());
 
-        InstanceHandle(Rc::new(Cell::new(i)))
+        self.textures.push(TextureData { width, height, pixels });
+
+        texture
+    }
+
+    // NOTE: None if the file could not be read as a PNG
+    #[cfg(feature = "png")]
+    pub fn load_texture(&mut self, path: impl AsRef<Path>) -> Option<Texture> {
+        let (width, height, pixels) = texture::read_png(path.as_ref())?;
+
+        Some(self.add_texture(width, height, pixels))
+    }
+
+    // uploads the glyph atlas of `font`, the texture of every `Glyph` it lays out
+    #[cfg(feature = "text")]
+    pub fn add_font(&mut self, font: &Font) -> Texture {
+        let (width, height, pixels) = font.atlas();
+
+        self.add_texture(width, height, pixels)
+    }
+
+    // an instance of M per laid out glyph, see `Font::layout`,
+    // `to_instance` fills in the rest, like the color and the texture from `Self::add_font`
+    #[cfg(feature = "text")]
+    pub fn add_text<M: Mesh>(
+        &mut self,
+        font:        &Font,
+        text:        &str,
+        origin:      [f32; 2],
+        style:       &TextStyle,
+        to_instance: impl FnMut(Glyph) -> I
+    ) -> Vec<InstanceHandle<I>> {
+        font
+            .instances(text, origin, style, to_instance)
+            .map(|instance| self.add_instance::<M>(instance))
+            .collect()
+    }
+
+    // fills the freed slot with the last instance of the same mesh,
+    // returns the (from, to) offsets of the moved instance, if there was one
+    fn remove_instance(&mut self, InstanceHandle(offset, _): InstanceHandle<I>) -> Option<(usize, usize)> {
+        let i = offset.get();
+
+        let (key, chunk_index) = self.instance_datas_per_mesh
+            .iter()
+            .find_map(|(key, instance_datas)| {
+                let chunk_capacity = self.registered[key].instance_count_estimate;
+
+                instance_datas
+                    .iter()
+                    .position(|instance_data| {
+                        (instance_data.chunk_offset..instance_data.chunk_offset + chunk_capacity).contains(&i)
+                    })
+                    .map(|chunk_index| (key.clone(), chunk_index))
+            })?;
+
+        let instance_datas = self.instance_datas_per_mesh.get_mut(&key)?;
+
+        let last_chunk_index = instance_datas
+            .iter()
+            .rposition(|instance_data| instance_data.count != 0)?;
+        let last_instance_data = &mut instance_datas[last_chunk_index];
+
+        last_instance_data.count -= 1;
+
+        let last_handle = last_instance_data.handles.pop()?;
+        let last_i      = last_instance_data.chunk_offset + last_instance_data.count;
+
+        if Rc::ptr_eq(&last_handle, &offset) {
+            // the removed instance was the last one, nothing to move
+            return None;
+        }
+
+        let instance_data = &mut instance_datas[chunk_index];
+        let slot          = i - instance_data.chunk_offset;
+
+        last_handle.set(i);
+        instance_data.handles[slot] = last_handle;
+
+        self.instances[i] = self.instances[last_i];
+
+        Some((last_i, i))
+    }
+
+    // NOTE: `shader_vertex_strides` are per created pipeline
+    fn check_vertex_strides(&self, shader_vertex_strides: &[Option<usize>]) {
+        for (key, mesh_data) in &self.registered {
+            let Some(&Some(stride)) = shader_vertex_strides.get(mesh_data.pipeline.0) else {
+                continue;
+            };
+
+            assert!(
+                mesh_data.vertex_stride == stride,
+                "`{key}` vertices are {} bytes, but the `Vertex` in `{}` is {stride} bytes",
+                mesh_data.vertex_stride,
+                self.pipelines[mesh_data.pipeline.0].vertex.name()
+            );
+        }
     }
 
     #[inline]
@@ -181,7 +400,7 @@ impl Meshes {
         &self,
         vk:             &Vulkan,
         command_buffer: vk::CommandBuffer,
-        renderer:       &Renderer
+        renderer:       &Renderer<I>
     ) {
         // TODO: overwrite offseted ranges of bytes, instead of changing len
         //       easy when it will be incorporated into the type system or with proc-macros
@@ -191,25 +410,49 @@ impl Meshes {
 
         let cut_off1 = push_constants.len();
 
-        // TODO: dont do `/ {VERTEX/INSTANCE}_SIZE` here
-        //       rather do more work in Self::add_instance
-        for (key, instance_datas) in &self.instance_datas_per_mesh {
+        let view_projection   = renderer.camera.view_projection();
+        let screen_projection = camera::screen_projection(renderer.image_extent.width as f32, renderer.image_extent.height as f32);
+
+        let mut bound_pipeline = None;
+
+        for key in &self.draw_order {
+            let Some(instance_datas) = self.instance_datas_per_mesh.get(key) else {
+                continue;
+            };
             let mesh_data = &self.registered[key];
 
+            if bound_pipeline != Some(mesh_data.pipeline) {
+                let (pipeline, _) = renderer.pipelines[mesh_data.pipeline.0];
+                unsafe { vk.device.cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::GRAPHICS, pipeline); }
+
+                bound_pipeline = Some(mesh_data.pipeline);
+            }
+
             push_constants.truncate(cut_off1);
-            push_constants.extend((mesh_data.index_offset as u32).to_le_bytes());
+            push_constants.extend(u32::try_from(mesh_data.index_offset).unwrap().to_le_bytes());
+            push_constants.resize(VIEW_PROJECTION_OFFSET, 0);
+
+            let projection = match self.pipelines[mesh_data.pipeline.0].space {
+                Space::World  => &view_projection,
+                Space::Screen => &screen_projection
+            };
+            push_constants.extend(projection.iter().flatten().flat_map(|value| value.to_le_bytes()));
 
             for instance_data in instance_datas {
+                if instance_data.count == 0 {
+                    continue;
+                }
+
                 unsafe {
                     vk.device.cmd_push_constants(command_buffer, renderer.graphics_pipeline_layout, vk::ShaderStageFlags::VERTEX, 0, &push_constants);
 
                     // NOTE: its indexed inside the shader
                     vk.device.cmd_draw(
                         command_buffer,
-                        mesh_data.index_count as u32,
-                        instance_data.count   as u32,
-                        (mesh_data.vertex_offset / VERTEX_SIZE) as u32,
-                        (instance_data.chunk_offset / INSTANCE_SIZE) as u32
+                        u32::try_from(mesh_data.index_count)      .unwrap(),
+                        u32::try_from(instance_data.count)        .unwrap(),
+                        u32::try_from(mesh_data.vertex_offset)    .unwrap(),
+                        u32::try_from(instance_data.chunk_offset) .unwrap()
                     );
                 }
             }
@@ -217,41 +460,229 @@ impl Meshes {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct VulkanConfig {
+    // the highest scoring device by default, `device::DEVICE_ENV_VAR` overrides it
+    pub device:                    Option<DeviceSelector>,
+    // the file the pipeline cache is loaded from and saved to, only kept in memory without one,
+    // `pipeline_cache::PIPELINE_CACHE_ENV_VAR` overrides it
+    pub pipeline_cache:            Option<PathBuf>,
+    // `VK_LAYER_KHRONOS_validation` with its messages forwarded to `log`, if it is installed,
+    // `validation::VALIDATION_ENV_VAR` enables it too
+    #[cfg(feature = "validation")]
+    pub validation:                bool,
+    // checked after every frame, meant for tests
+    #[cfg(feature = "validation")]
+    pub panic_on_validation_error: bool
+}
+
+#[derive(Clone)]
+pub struct RendererConfig {
+    // the first one the surface supports in its fallback chain, see `PresentMode`,
+    // `Renderer::set_present_mode` changes it later
+    pub present_mode:     PresentMode,
+    // the first one the surface supports in its fallback chain, see `ColorSpace`
+    pub color_space:      ColorSpace,
+    // how many frames the cpu can be ahead of the gpu, independent of the swapchain image count
+    pub frames_in_flight: u32
+}
+
+impl Default for RendererConfig {
+    fn default() -> Self {
+        Self {
+            present_mode:     PresentMode::default(),
+            color_space:      ColorSpace::default(),
+            frames_in_flight: 2
+        }
+    }
+}
+
 pub struct Vulkan {
-    entry:           ash::Entry,
-    instance:        ash::Instance,
-    physical_device: vk::PhysicalDevice,
-    device:          ash::Device,
-    queue:           vk::Queue,
-    ext_surface:     khr::surface::Instance,
-    ext_swapchain:   khr::swapchain::Device
+    entry:            ash::Entry,
+    instance:         ash::Instance,
+    physical_device:  vk::PhysicalDevice,
+    device_info:      DeviceInfo,
+    queue_families:   QueueFamilies,
+    device:           ash::Device,
+    // graphics and compute
+    queue:            vk::Queue,
+    // the same as `queue` if the family is the same, or without a window
+    present_queue:    vk::Queue,
+    ext_surface:      khr::surface::Instance,
+    ext_swapchain:    khr::swapchain::Device,
+    allocator:        RefCell<Allocator>,
+    pipeline_cache:   PipelineCache,
+    // nanoseconds per tick, None if the graphics queue can not write timestamps
+    timestamp_period: Option<f32>,
+    #[cfg(feature = "validation")]
+    debug_messenger:  Option<DebugMessenger>
 }
 
 impl Vulkan {
+    /// NOTE: the window is only used to pick a device that can present to it
+    ///
+    /// # Errors
+    /// when vulkan is missing, or when no device can present to the window
+    pub fn new(
+        handle: impl HasDisplayHandle + HasWindowHandle,
+        config: &VulkanConfig
+    ) -> Result<Self, Error> {
+        let rdh = handle
+            .display_handle()
+            .map_err(Error::WindowHandle)?
+            .into();
+        let rwh = handle
+            .window_handle()
+            .map_err(Error::WindowHandle)?
+            .into();
+
+        let instance_extensions = ash_window::enumerate_required_extensions(rdh)
+            .map_err(Error::Surface)?;
+
+        Self::with_window(instance_extensions, Some((rdh, rwh)), config)
+    }
+
+    /// # Errors
+    /// when vulkan is missing, or when no device can be used
+    pub fn new_headless(config: &VulkanConfig) -> Result<Self, Error> {
+        Self::with_window(&[], None, config)
+    }
+
+    #[must_use]
+    pub const fn device_info(&self) -> &DeviceInfo {
+        &self.device_info
+    }
+
     #[must_use]
-    pub fn new(instance_extensions: &'static [*const ffi::c_char]) -> Self {
+    pub const fn queue_families(&self) -> QueueFamilies {
+        self.queue_families
+    }
+
+    fn with_window(
+        required_extensions: &[*const ffi::c_char],
+        window:              Option<(RawDisplayHandle, RawWindowHandle)>,
+        config:              &VulkanConfig
+    ) -> Result<Self, Error> {
         let entry = unsafe { ash::Entry::load() }
-            .unwrap();
+            .map_err(Error::Loader)?;
+
+        // NOTE: for the HDR color spaces, see `ColorSpace`
+        let has_swapchain_colorspace = unsafe { entry.enumerate_instance_extension_properties(None) }
+            .map_err(Error::Instance)?
+            .iter()
+            .any(|extension| extension.extension_name_as_c_str() == Ok(ext::swapchain_colorspace::NAME));
+
+        let mut instance_extensions = required_extensions.to_vec();
+
+        if window.is_some() && has_swapchain_colorspace {
+            instance_extensions.push(ext::swapchain_colorspace::NAME.as_ptr());
+        }
 
         let application_info = vk::ApplicationInfo::default()
             .application_name(c"dacho")
             .api_version(vk::API_VERSION_1_3);
-        let instance_create_info = vk::InstanceCreateInfo::default()
-            .enabled_extension_names(instance_extensions)
-            .application_info(&application_info);
-        let instance = unsafe { entry.create_instance(&instance_create_info, None) }
-            .unwrap();
-
-        let physical_device = unsafe { instance.enumerate_physical_devices() }
-            .unwrap()
-            .swap_remove(0);
+        #[cfg(feature = "validation")]
+        let (instance, debug_messenger) = DebugMessenger::create_instance(
+            &entry,
+            &application_info,
+            &instance_extensions,
+            config.validation,
+            config.panic_on_validation_error
+        )?;
+        #[cfg(not(feature = "validation"))]
+        let instance = {
+            let instance_create_info = vk::InstanceCreateInfo::default()
+                .enabled_extension_names(&instance_extensions)
+                .application_info(&application_info);
+
+            unsafe { entry.create_instance(&instance_create_info, None) }
+                .map_err(Error::Instance)?
+        };
 
-        let queue_create_infos = [
-            vk::DeviceQueueCreateInfo::default()
-                .queue_family_index(0)
-                .queue_priorities(&[1.0])
-        ];
-        let enabled_extension_names = Box::leak(Box::new([vk::KHR_SWAPCHAIN_NAME.as_ptr()]));
+        let ext_surface = khr::surface::Instance::new(&entry, &instance);
+
+        // NOTE: nothing else to clean up, the instance is the only thing created so far
+        let (physical_device, device_info, queue_families, device) = match Self::create_device(&entry, &instance, &ext_surface, window, config) {
+            Ok(created) => created,
+            Err(err)    => {
+                #[cfg(feature = "validation")]
+                if let Some(messenger) = debug_messenger.as_ref() {
+                    messenger.destroy();
+                }
+
+                unsafe { instance.destroy_instance(None); }
+
+                return Err(err);
+            }
+        };
+
+        let queue         = unsafe { device.get_device_queue(queue_families.graphics, 0) };
+        let present_queue = unsafe { device.get_device_queue(queue_families.present.unwrap_or(queue_families.graphics), 0) };
+
+        let ext_swapchain = khr::swapchain::Device::new(&instance, &device);
+
+        let memory_properties = unsafe { instance.get_physical_device_memory_properties(physical_device) };
+        let allocator         = RefCell::new(Allocator::new(&memory_properties));
+
+        let properties     = unsafe { instance.get_physical_device_properties(physical_device) };
+        let pipeline_cache = PipelineCache::new(&device, &properties, config.pipeline_cache.as_deref());
+
+        let queue_family_properties = unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
+        let timestamp_period        = (queue_family_properties[queue_families.graphics as usize].timestamp_valid_bits != 0)
+            .then_some(properties.limits.timestamp_period);
+
+        Ok(Self {
+            entry,
+            instance,
+            physical_device,
+            device_info,
+            queue_families,
+            device,
+            queue,
+            present_queue,
+            ext_surface,
+            ext_swapchain,
+            allocator,
+            pipeline_cache,
+            timestamp_period,
+            #[cfg(feature = "validation")]
+            debug_messenger
+        })
+    }
+
+    fn create_device(
+        entry:       &ash::Entry,
+        instance:    &ash::Instance,
+        ext_surface: &khr::surface::Instance,
+        window:      Option<(RawDisplayHandle, RawWindowHandle)>,
+        config:      &VulkanConfig
+    ) -> Result<(vk::PhysicalDevice, DeviceInfo, QueueFamilies, ash::Device), Error> {
+        let (physical_device, device_info, queue_families) = if let Some((rdh, rwh)) = window {
+            // NOTE: the renderer creates its own surface
+            let surface = unsafe { ash_window::create_surface(entry, instance, rdh, rwh, None) }
+                .map_err(Error::Surface)?;
+
+            let selected = device::select(instance, Some((ext_surface, surface)), config.device.as_ref());
+
+            unsafe { ext_surface.destroy_surface(surface, None); }
+
+            selected?
+        } else {
+            device::select(instance, None, config.device.as_ref())?
+        };
+
+        let queue_create_infos = queue_families
+            .unique()
+            .into_iter()
+            .map(|queue_family_index| {
+                vk::DeviceQueueCreateInfo::default()
+                    .queue_family_index(queue_family_index)
+                    .queue_priorities(&[1.0])
+            })
+            .collect::<Vec<_>>();
+        // NOTE: headless devices do not need to support swapchains
+        let swapchain_extension_names = [vk::KHR_SWAPCHAIN_NAME.as_ptr()];
+        let enabled_extension_names   = if window.is_some() { swapchain_extension_names.as_slice() } else { &[] };
         let enabled_features = vk::PhysicalDeviceFeatures::default()
             .logic_op(true)
             .shader_int64(true);
@@ -259,7 +690,11 @@ impl Vulkan {
             .dynamic_rendering(true)
             .synchronization2(true);
         let mut vulkan12_extensions = vk::PhysicalDeviceVulkan12Features::default()
-            .buffer_device_address(true);
+            .buffer_device_address(true)
+            .runtime_descriptor_array(true)
+            .descriptor_binding_partially_bound(true)
+            .descriptor_binding_sampled_image_update_after_bind(true)
+            .shader_sampled_image_array_non_uniform_indexing(true);
         let mut vulkan11_extensions = vk::PhysicalDeviceVulkan11Features::default()
             .shader_draw_parameters(true);
         let device_create_info = vk::DeviceCreateInfo::default()
@@ -270,49 +705,48 @@ impl Vulkan {
             .push_next(&mut vulkan12_extensions)
             .push_next(&mut vulkan11_extensions);
         let device = unsafe { instance.create_device(physical_device, &device_create_info, None) }
-            .unwrap();
-
-        let queue = unsafe { device.get_device_queue(0, 0) };
+            .map_err(Error::Device)?;
 
-        let ext_surface   = khr::surface::Instance::new(&entry,    &instance);
-        let ext_swapchain = khr::swapchain::Device::new(&instance, &device  );
-
-        Self {
-            entry,
-            instance,
-            physical_device,
-            device,
-            queue,
-            ext_surface,
-            ext_swapchain
-        }
+        Ok((physical_device, device_info, queue_families, device))
     }
 
-    #[must_use]
-    pub fn new_renderer(
+    /// # Errors
+    /// when the surface, swapchain or any of the buffers can not be created
+    pub fn new_renderer<I: Instance>(
         &self,
         handle:      impl HasDisplayHandle + HasWindowHandle,
         width:       u32,
         height:      u32,
         clear_color: [f32; 4],
-        meshes:      Meshes
-    ) -> Renderer {
-        Renderer::new(self, handle, width, height, clear_color, meshes)
+        meshes:      Meshes<I>,
+        config:      &RendererConfig
+    ) -> Result<Renderer<I>, Error> {
+        Renderer::new(self, handle, width, height, clear_color, meshes, config)
     }
 
-    pub fn destroy_renderer(&self, renderer: Renderer) {
+    /// # Errors
+    /// when the offscreen image or any of the buffers can not be created
+    pub fn new_headless_renderer<I: Instance>(
+        &self,
+        width:       u32,
+        height:      u32,
+        clear_color: [f32; 4],
+        meshes:      Meshes<I>
+    ) -> Result<Renderer<I>, Error> {
+        Renderer::new_headless(self, width, height, clear_color, meshes)
+    }
+
+    pub fn destroy_renderer<I: Instance>(&self, renderer: Renderer<I>) {
         renderer.destroy(self);
     }
 
-    // TODO: make one big allocation from which smaller chunks are taken
-    #[must_use]
-    fn create_buffer<T>(
+    fn create_raw_buffer(
         &self,
-        data:  &[T],
-        usage: vk::BufferUsageFlags
-    ) -> ((vk::Buffer, vk::DeviceMemory), *mut T) {
-        let len                = data.len();
-        let size               = mem::size_of_val(data) as u64;
+        size:      u64,
+        usage:     vk::BufferUsageFlags,
+        required:  vk::MemoryPropertyFlags,
+        preferred: vk::MemoryPropertyFlags
+    ) -> Result<(vk::Buffer, Allocation), Error> {
         let buffer_create_info = vk::BufferCreateInfo::default()
             .size(size)
             .usage(
@@ -323,213 +757,1420 @@ impl Vulkan {
             )
             .sharing_mode(vk::SharingMode::EXCLUSIVE);
         let buffer = unsafe { self.device.create_buffer(&buffer_create_info, None) }
-            .unwrap();
+            .map_err(Error::Memory)?;
         let memory_requirements = unsafe { self.device.get_buffer_memory_requirements(buffer) };
-        let memory_properties   = unsafe { self.instance.get_physical_device_memory_properties(self.physical_device) };
-        let memory_type_index   = find_memory_type_index(
-            &memory_properties,
-            memory_requirements.memory_type_bits,
-            vk::MemoryPropertyFlags::DEVICE_LOCAL  | // TODO: do i need this?
-            vk::MemoryPropertyFlags::HOST_VISIBLE  |
-            vk::MemoryPropertyFlags::HOST_COHERENT
-        ).unwrap();
-
-        let mut memory_allocate_flags_info = vk::MemoryAllocateFlagsInfo::default()
-            .flags(vk::MemoryAllocateFlags::DEVICE_ADDRESS);
-        let memory_allocate_info = vk::MemoryAllocateInfo::default()
-            .allocation_size(memory_requirements.size)
-            .memory_type_index(memory_type_index)
-            .push_next(&mut memory_allocate_flags_info);
-        let device_memory = unsafe { self.device.allocate_memory(&memory_allocate_info, None) }
-            .unwrap();
-
-        unsafe { self.device.bind_buffer_memory(buffer, device_memory, 0) }
-            .unwrap();
+        let allocation          = self.allocator
+            .borrow_mut()
+            .allocate(&self.device, memory_requirements, required, preferred, true)
+            .map_err(Error::Memory)?;
 
-        let src = data.as_ptr();
-        let dst = unsafe { self.device.map_memory(device_memory, 0, size, vk::MemoryMapFlags::empty()) }
-            .unwrap()
-            .cast::<T>();
-
-        unsafe { ptr::copy_nonoverlapping(src, dst, len); }
-
-        ((buffer, device_memory), dst)
-    }
+        unsafe { self.device.bind_buffer_memory(buffer, allocation.memory(), allocation.offset()) }
+            .map_err(Error::Memory)?;
 
-    #[inline]
-    pub fn device_wait_idle(&self) {
-        unsafe { self.device.device_wait_idle() }
-            .unwrap();
+        Ok((buffer, allocation))
     }
 
-    #[inline]
-    pub fn render(
+    // host visible and mapped, for data that changes often (and staging and readback),
+    // device local too if there is such memory (like with resizable BAR)
+    fn create_buffer<T>(
         &self,
-        renderer:                 &mut Renderer,
-        winit_pre_present_notify: impl Fn()
-    ) {
-        let fi = renderer.frame_index as usize;
-
-        let in_flight_fence           = renderer.in_flight_fences          [fi];
-        let image_ready_semaphore     = renderer.image_ready_semaphores    [fi];
-        let render_finished_semaphore = renderer.render_finished_semaphores[fi];
-        let command_buffer            = renderer.command_buffers           [fi];
+        data:     &[T],
+        capacity: usize,
+        usage:    vk::BufferUsageFlags
+    ) -> Result<((vk::Buffer, Allocation), *mut T), Error> {
+        let len  = data.len();
+        // NOTE: zero sized buffers are not allowed
+        let size = (capacity.max(len).max(1) * mem::size_of::<T>()) as u64;
+
+        let buffer = self.create_raw_buffer(
+            size,
+            usage,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL
+        )?;
 
-        self.wait_for_and_reset_fences(in_flight_fence);
-
-        let image_index = self.acquire_next_image(renderer.swapchain, image_ready_semaphore);
-        let image       = renderer.swapchain_images[image_index as usize];
-
-        self.reset_command_buffer(command_buffer);
-
-        self.with_command_buffer(command_buffer, || {
-            // compute
-            {
-                unsafe {
-                    self.device.cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::COMPUTE, renderer.compute_pipeline);
-                }
+        let src = data.as_ptr();
+        let dst = buffer.1.mapped().cast::<T>();
 
-                let particle_count: u32 = 10 * 10;
-                let group_size_x        = 128; // compute shader's local_size_x
-                let group_count_x       = particle_count.div_ceil(group_size_x);
+        unsafe { ptr::copy_nonoverlapping(src, dst, len); }
 
-                // TODO: clean this up (make a new push layout for compute, currently its just copied from graphics)
-                let mut push_constants = renderer. vertices_pointer.to_le_bytes().to_vec();
-                push_constants.extend(   renderer.  indices_pointer.to_le_bytes());
-                push_constants.extend(   renderer.instances_pointer.to_le_bytes());
-                push_constants.extend(                        0_u32.to_le_bytes());
+        Ok((buffer, dst))
+    }
 
-                unsafe {
-                    self.device.cmd_push_constants(command_buffer, renderer.compute_pipeline_layout, vk::ShaderStageFlags::COMPUTE, 0, &push_constants);
-                    self.device.cmd_dispatch(command_buffer, group_count_x, 1, 1);
-                }
+    // device local, uploaded through a staging buffer, for data that (almost) never changes
+    fn create_static_buffer<T>(
+        &self,
+        command_pool: vk::CommandPool,
+        data:         &[T],
+        usage:        vk::BufferUsageFlags
+    ) -> Result<((vk::Buffer, Allocation), u64), Error> {
+        let size = mem::size_of_val(data) as u64;
+
+        // NOTE: zero sized buffers are not allowed
+        let buffer = self.create_raw_buffer(
+            size.max(1),
+            usage,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            vk::MemoryPropertyFlags::empty()
+        )?;
+
+        if size != 0 {
+            let (staging, _) = self.create_buffer(data, data.len(), vk::BufferUsageFlags::TRANSFER_SRC)?;
+
+            let submitted = self.one_time_submit(command_pool, |command_buffer| {
+                let regions = [
+                    vk::BufferCopy::default()
+                        .src_offset(0)
+                        .dst_offset(0)
+                        .size(size)
+                ];
+                unsafe { self.device.cmd_copy_buffer(command_buffer, staging.0, buffer.0, &regions); }
 
                 let buffer_memory_barriers = [
                     vk::BufferMemoryBarrier2::default()
-                        .src_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER)
-                        .src_access_mask(vk::AccessFlags2::SHADER_WRITE)
-                        .dst_stage_mask(vk::PipelineStageFlags2::VERTEX_SHADER)
-                        .dst_access_mask(vk::AccessFlags2::SHADER_READ)
-                        .buffer(renderer.instances.0)
+                        .src_stage_mask(vk::PipelineStageFlags2::COPY)
+                        .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+                        .dst_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER | vk::PipelineStageFlags2::VERTEX_SHADER)
+                        .dst_access_mask(vk::AccessFlags2::SHADER_READ | vk::AccessFlags2::SHADER_WRITE)
+                        .buffer(buffer.0)
                         .offset(0)
                         .size(vk::WHOLE_SIZE)
                 ];
                 let dependency_info = vk::DependencyInfo::default()
                     .buffer_memory_barriers(&buffer_memory_barriers);
                 unsafe { self.device.cmd_pipeline_barrier2(command_buffer, &dependency_info); }
-            }
+            });
 
-            // graphics
-            {
-                self.with_image_memory_barriers(image, renderer, command_buffer, || {
-                    self.with_dynamic_rendering(renderer, image_index, command_buffer, || {
-                        unsafe {
-                            self.device.cmd_set_viewport(command_buffer, 0, &renderer.viewports);
-                            self.device.cmd_set_scissor(command_buffer, 0, &renderer.scissors);
-                            self.device.cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::GRAPHICS, renderer.graphics_pipeline);
-                        }
-
-                        renderer.meshes.draw(self, command_buffer, renderer);
-                    });
-                });
-            }
-        });
+            self.destroy_buffer(staging);
 
-        winit_pre_present_notify();
+            submitted?;
+        }
 
-        self.submit_and_present(
-            image_ready_semaphore,
-            command_buffer,
-            render_finished_semaphore,
-            in_flight_fence,
-            renderer.swapchain,
-            image_index
-        );
+        Ok((buffer, self.get_buffer_device_address(buffer.0)))
+    }
+
+    // NOTE: device local, optimal tiling
+    fn allocate_image_memory(&self, image: vk::Image) -> Result<Allocation, Error> {
+        let memory_requirements = unsafe { self.device.get_image_memory_requirements(image) };
+        let allocation          = self.allocator
+            .borrow_mut()
+            .allocate(
+                &self.device,
+                memory_requirements,
+                vk::MemoryPropertyFlags::DEVICE_LOCAL,
+                vk::MemoryPropertyFlags::empty(),
+                false
+            )
+            .map_err(Error::Memory)?;
+
+        unsafe { self.device.bind_image_memory(image, allocation.memory(), allocation.offset()) }
+            .map_err(Error::Memory)?;
 
-        renderer.frame_index = (renderer.frame_index + 1) % renderer.max_frames_in_flight;
+        Ok(allocation)
     }
 
-    #[inline]
-    pub fn resize(&self, renderer: &mut Renderer, width: u32, height: u32) {
-        self.device_wait_idle();
+    fn free_memory(&self, allocation: Allocation) {
+        self.allocator
+            .borrow_mut()
+            .free(&self.device, allocation);
+    }
 
-        let (
-            image_extent,
-            subresource_range,
-            swapchain,
-            swapchain_images,
-            swapchain_image_views,
-            viewports,
-            scissors,
-            _
-        ) = self.create_swapchain_and_everything_related(renderer.surface, width, height, renderer.swapchain);
+    #[must_use]
+    pub fn memory_stats(&self) -> MemoryStats {
+        self.allocator
+            .borrow()
+            .stats()
+    }
 
-        renderer.destroy_swapchain_and_image_views(self);
+    #[must_use]
+    fn get_buffer_device_address(&self, buffer: vk::Buffer) -> u64 {
+        let buffer_device_address_info = vk::BufferDeviceAddressInfo::default()
+            .buffer(buffer);
 
-        renderer.image_extent          = image_extent;
-        renderer.subresource_range     = subresource_range;
-        renderer.swapchain             = swapchain;
-        renderer.swapchain_images      = swapchain_images;
-        renderer.swapchain_image_views = swapchain_image_views;
-        renderer.viewports             = viewports;
-        renderer.scissors              = scissors;
+        unsafe { self.device.get_buffer_device_address(&buffer_device_address_info) }
     }
 
-    #[inline]
-    fn create_swapchain_and_everything_related(
+    fn destroy_buffer(&self, buffer: (vk::Buffer, Allocation)) {
+        unsafe { self.device.destroy_buffer(buffer.0, None); }
+        self.free_memory(buffer.1);
+    }
+
+    fn destroy_offscreen(&self, offscreen: &Offscreen) {
+        self.destroy_buffer(offscreen.readback);
+
+        unsafe { self.device.destroy_image(offscreen.image, None); }
+        self.free_memory(offscreen.image_memory);
+    }
+
+    // NOTE: headless targets have a null surface and swapchain, and the offscreen image instead
+    fn destroy_target(
         &self,
-        surface:       vk::SurfaceKHR,
-        width:         u32,
-        height:        u32,
-        old_swapchain: vk::SwapchainKHR
-    ) -> SwapchainAndEverythingRelated {
-        let image_extent = vk::Extent2D { width, height };
+        surface:               vk::SurfaceKHR,
+        swapchain:             vk::SwapchainKHR,
+        swapchain_image_views: &[vk::ImageView],
+        offscreen:             Option<&Offscreen>
+    ) {
+        unsafe {
+            for image_view in swapchain_image_views {
+                self.device.destroy_image_view(*image_view, None);
+            }
 
-        let subresource_range = vk::ImageSubresourceRange::default()
-            .aspect_mask(vk::ImageAspectFlags::COLOR)
-            .base_mip_level(0)
-            .level_count(1)
-            .base_array_layer(0)
-            .layer_count(1);
+            if swapchain != vk::SwapchainKHR::null() {
+                self.ext_swapchain.destroy_swapchain(swapchain, None);
+            }
+        }
 
-        let surface_capabilities = unsafe { self.ext_surface.get_physical_device_surface_capabilities(self.physical_device, surface) }
-            .unwrap();
-        let max_frames_in_flight = 4; // surface_capabilities.min_image_count + 1;
-        let swapchain_create_info = vk::SwapchainCreateInfoKHR::default()
-            .old_swapchain(old_swapchain)
-            .surface(surface)
-            .image_format(SWAPCHAIN_FORMAT)
-            .image_extent(image_extent)
-            .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
-            .image_array_layers(1)
-            .min_image_count(max_frames_in_flight)
-            .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
-            .pre_transform(surface_capabilities.current_transform)
-            .clipped(true)
-            .present_mode(vk::PresentModeKHR::FIFO);
-        let swapchain = unsafe { self.ext_swapchain.create_swapchain(&swapchain_create_info, None) }
-            .unwrap();
+        if let Some(target_offscreen) = offscreen {
+            self.destroy_offscreen(target_offscreen);
+        }
 
-        let swapchain_images = unsafe { self.ext_swapchain.get_swapchain_images(swapchain) }
-            .unwrap();
+        unsafe { self.ext_surface.destroy_surface(surface, None); }
+    }
 
-        assert!(swapchain_images.len() == max_frames_in_flight as usize, "swapchain image count error");
+    // uploads whatever was registered/added through the Renderer since the last frame
+    fn sync_meshes<I: Instance>(&self, renderer: &mut Renderer<I>) -> Result<(), Error> {
+        #[cfg(feature = "hot_reload")]
+        self.reload_changed_pipelines(renderer)?;
+        self.sync_pipelines(renderer)?;
+        self.sync_textures(renderer)?;
 
-        let swapchain_image_views = swapchain_images
-            .iter()
-            .map(|image| {
-                let image_view_create_info = vk::ImageViewCreateInfo::default()
-                    .image(*image)
-                    .view_type(vk::ImageViewType::TYPE_2D)
-                    .format(SWAPCHAIN_FORMAT)
-                    .subresource_range(subresource_range);
+        let vertices_changed = renderer.meshes.vertices.len() != renderer.vertices_len;
+        let  indices_changed = renderer.meshes. indices.len() != renderer. indices_len;
+        let instances_grew   = renderer.meshes.instances.len() > renderer.instances_capacity;
+
+        if !(vertices_changed || indices_changed || instances_grew) {
+            return Ok(());
+        }
+
+        // NOTE: frames in flight may still be using the old buffers,
+        //       this only happens when something outgrows them, so a stall is fine
+        self.device_wait_idle()?;
+
+        // NOTE: the old buffers are only destroyed once their replacements exist,
+        //       so a failed creation leaves the renderer with valid handles to destroy
+
+        if vertices_changed {
+            let (vertices, vertices_pointer) = self.create_static_buffer(
+                renderer.command_pool,
+                &renderer.meshes.vertices,
+                vk::BufferUsageFlags::VERTEX_BUFFER
+            )?;
+
+            self.destroy_buffer(renderer.vertices);
+
+            (renderer.vertices, renderer.vertices_pointer) = (vertices, vertices_pointer);
+            renderer.vertices_len = renderer.meshes.vertices.len();
+        }
+
+        if indices_changed {
+            let (indices, indices_pointer) = self.create_static_buffer(
+                renderer.command_pool,
+                &renderer.meshes.indices,
+                vk::BufferUsageFlags::INDEX_BUFFER
+            )?;
+
+            self.destroy_buffer(renderer.indices);
+
+            (renderer.indices, renderer.indices_pointer) = (indices, indices_pointer);
+            renderer.indices_len = renderer.meshes.indices.len();
+        }
+
+        if instances_grew {
+            // NOTE: the mapped buffer holds the latest values of everything it had room for
+            let old_capacity = renderer.instances_capacity;
+            let src          = renderer.mapped_instances_dst.cast_const();
+            let dst          = renderer.meshes.instances.as_mut_ptr();
+            unsafe { ptr::copy_nonoverlapping(src, dst, old_capacity); }
+
+            let new_capacity = renderer.meshes.instances.len().max(old_capacity * 2);
+
+            let (instances, mapped_instances_dst) = self.create_buffer(
+                &renderer.meshes.instances,
+                new_capacity,
+                vk::BufferUsageFlags::VERTEX_BUFFER
+            )?;
+
+            self.destroy_buffer(renderer.instances);
+
+            (renderer.instances, renderer.mapped_instances_dst) = (instances, mapped_instances_dst);
+            renderer.instances_pointer  = self.get_buffer_device_address(renderer.instances.0);
+            renderer.instances_capacity = new_capacity;
+        }
+
+        Ok(())
+    }
+
+    fn sync_pipelines<I: Instance>(&self, renderer: &mut Renderer<I>) -> Result<(), Error> {
+        for description in &renderer.meshes.compute_passes[renderer.compute_passes.len()..] {
+            let compute_pass = self.create_compute_pipeline::<I>(renderer.compute_pipeline_layout, &description.shader)
+                .map_err(Error::Shader)?;
+
+            renderer.compute_passes.push(compute_pass);
+
+            #[cfg(feature = "hot_reload")]
+            renderer.shader_watcher.compute.push(hot_reload::last_modified(&[&description.shader]));
+        }
+
+        if renderer.pipelines.len() == renderer.meshes.pipelines.len() {
+            return Ok(());
+        }
+
+        for description in &renderer.meshes.pipelines[renderer.pipelines.len()..] {
+            let pipeline = self.create_graphics_pipeline::<I>(renderer.graphics_pipeline_layout, renderer.surface_format.format, description)
+                .map_err(Error::Shader)?;
+
+            renderer.pipelines.push(pipeline);
+
+            #[cfg(feature = "hot_reload")]
+            renderer.shader_watcher.graphics.push(hot_reload::last_modified(&[&description.vertex, &description.fragment]));
+        }
+
+        renderer.check_vertex_strides();
+
+        Ok(())
+    }
+
+    // rebuilds the pipelines whose shader files changed, keeping the old ones on errors
+    #[cfg(feature = "hot_reload")]
+    fn reload_changed_pipelines<I: Instance>(&self, renderer: &mut Renderer<I>) -> Result<(), Error> {
+        if !renderer.shader_watcher.should_poll() {
+            return Ok(());
+        }
+
+        for (i, description) in renderer.meshes.pipelines.iter().enumerate().take(renderer.pipelines.len()) {
+            let last_modified = hot_reload::last_modified(&[&description.vertex, &description.fragment]);
+
+            if last_modified <= renderer.shader_watcher.graphics[i] {
+                continue;
+            }
+
+            renderer.shader_watcher.graphics[i] = last_modified;
+
+            let (old_pipeline, old_shader_vertex_stride) = renderer.pipelines[i];
+
+            match self.create_graphics_pipeline::<I>(renderer.graphics_pipeline_layout, renderer.surface_format.format, description) {
+                Ok((pipeline, shader_vertex_stride)) if shader_vertex_stride == old_shader_vertex_stride => {
+                    // NOTE: frames in flight may still be using the old pipeline
+                    self.device_wait_idle()?;
+                    unsafe { self.device.destroy_pipeline(old_pipeline, None); }
+
+                    renderer.pipelines[i].0 = pipeline;
+                },
+                Ok((pipeline, _)) => {
+                    unsafe { self.device.destroy_pipeline(pipeline, None); }
+
+                    log::error!(target: "dacho", "failed to reload `{}`: the `Vertex` size changed, which needs a restart", description.vertex.name());
+                },
+                Err(err) => log::error!(target: "dacho", "failed to reload a pipeline: {err}")
+            }
+        }
+
+        for (i, description) in renderer.meshes.compute_passes.iter().enumerate().take(renderer.compute_passes.len()) {
+            let last_modified = hot_reload::last_modified(&[&description.shader]);
+
+            if last_modified <= renderer.shader_watcher.compute[i] {
+                continue;
+            }
+
+            renderer.shader_watcher.compute[i] = last_modified;
+
+            match self.create_compute_pipeline::<I>(renderer.compute_pipeline_layout, &description.shader) {
+                Ok(compute_pass) => {
+                    self.device_wait_idle()?;
+                    unsafe { self.device.destroy_pipeline(renderer.compute_passes[i].0, None); }
+
+                    renderer.compute_passes[i] = compute_pass;
+                },
+                Err(err) => log::error!(target: "dacho", "failed to reload a compute pass: {err}")
+            }
+        }
+
+        Ok(())
+    }
+
+    // returns the pipeline, and the vertex stride in its vertex shader if it was found
+    //
+    // NOTE: Err is a message for the user
+    fn create_graphics_pipeline<I: Instance>(
+        &self,
+        layout:       vk::PipelineLayout,
+        color_format: vk::Format,
+        description:  &PipelineDescription
+    ) -> Result<(vk::Pipeline, Option<usize>), String> {
+        let vertex_code   = description.vertex  .read()?;
+        let fragment_code = description.fragment.read()?;
+        check_instance_stride::<I>(&vertex_code, &description.vertex.name())?;
+
+        // NOTE: relies on the `VertexBuffer { Vertex data[]; }` naming in the shaders
+        let shader_vertex_stride = find_array_stride(&vertex_code, "VertexBuffer");
+
+        let vertex_module_create_info = vk::ShaderModuleCreateInfo::default()
+            .code(&vertex_code);
+        let vertex_module = unsafe { self.device.create_shader_module(&vertex_module_create_info, None) }
+            .map_err(|err| format!("failed to create a shader module from `{}`: {err}", description.vertex.name()))?;
+        let fragment_module_create_info = vk::ShaderModuleCreateInfo::default()
+            .code(&fragment_code);
+        let fragment_module = unsafe { self.device.create_shader_module(&fragment_module_create_info, None) }
+            .map_err(|err| {
+                unsafe { self.device.destroy_shader_module(vertex_module, None); }
+
+                format!("failed to create a shader module from `{}`: {err}", description.fragment.name())
+            })?;
+
+        let entry_point = c"main";
+        let stages = [
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::VERTEX)
+                .module(vertex_module)
+                .name(entry_point),
+            vk::PipelineShaderStageCreateInfo::default()
+                .stage(vk::ShaderStageFlags::FRAGMENT)
+                .module(fragment_module)
+                .name(entry_point)
+        ];
+        let vertex_input_state = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&[])
+            .vertex_attribute_descriptions(&[]);
+        let input_assembly_state = vk::PipelineInputAssemblyStateCreateInfo::default()
+            .primitive_restart_enable(false)
+            .topology(description.topology);
+        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+            .viewport_count(1)
+            .scissor_count(1);
+        let rasterization_state = vk::PipelineRasterizationStateCreateInfo::default()
+            .rasterizer_discard_enable(false)
+            .polygon_mode(vk::PolygonMode::FILL)
+            .cull_mode(description.cull_mode)
+            .front_face(vk::FrontFace::COUNTER_CLOCKWISE)
+            .depth_bias_enable(false)
+            .line_width(1.0);
+        let multisample_state = vk::PipelineMultisampleStateCreateInfo::default()
+            .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+        let color_blend_attachments = [description.blend.attachment_state()];
+        let depth_stencil_state = vk::PipelineDepthStencilStateCreateInfo::default()
+            .depth_test_enable(false)
+            .depth_write_enable(false)
+            .depth_compare_op(vk::CompareOp::LESS_OR_EQUAL)
+            .depth_bounds_test_enable(false)
+            .stencil_test_enable(false);
+        let color_blend_state = vk::PipelineColorBlendStateCreateInfo::default()
+            .logic_op_enable(false)
+            .attachments(&color_blend_attachments);
+        let dynamic_state = vk::PipelineDynamicStateCreateInfo::default()
+            .dynamic_states(&[vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR]);
+
+        let color_attachment_formats = [color_format];
+        let mut rendering_info = vk::PipelineRenderingCreateInfo::default()
+            .color_attachment_formats(&color_attachment_formats);
+        let graphics_pipeline_create_infos = [
+            vk::GraphicsPipelineCreateInfo::default()
+                .stages(&stages)
+                .vertex_input_state(&vertex_input_state)
+                .input_assembly_state(&input_assembly_state)
+                .viewport_state(&viewport_state)
+                .rasterization_state(&rasterization_state)
+                .multisample_state(&multisample_state)
+                .depth_stencil_state(&depth_stencil_state)
+                .color_blend_state(&color_blend_state)
+                .dynamic_state(&dynamic_state)
+                .layout(layout)
+                .push_next(&mut rendering_info)
+        ];
+        let pipelines = unsafe { self.device.create_graphics_pipelines(self.pipeline_cache.handle, &graphics_pipeline_create_infos, None) };
+
+        unsafe { self.device.destroy_shader_module(  vertex_module, None); }
+        unsafe { self.device.destroy_shader_module(fragment_module, None); }
+
+        let pipeline = pipelines
+            .map_err(|(_, err)| format!("failed to create a pipeline from `{}` and `{}`: {err}", description.vertex.name(), description.fragment.name()))?
+            .swap_remove(0);
+
+        Ok((pipeline, shader_vertex_stride))
+    }
+
+    // returns the pipeline, and the local_size_x of its compute shader
+    //
+    // NOTE: Err is a message for the user
+    fn create_compute_pipeline<I: Instance>(&self, layout: vk::PipelineLayout, source: &ShaderSource) -> Result<(vk::Pipeline, u32), String> {
+        let compute_code = source.read()?;
+        check_instance_stride::<I>(&compute_code, &source.name())?;
+
+        let [local_size_x, _, _] = find_local_size(&compute_code)
+            .ok_or_else(|| format!("`{}` has no `local_size_x`, specialization constants are not supported", source.name()))?;
+
+        let compute_module_create_info = vk::ShaderModuleCreateInfo::default()
+            .code(&compute_code);
+        let compute_module = unsafe { self.device.create_shader_module(&compute_module_create_info, None) }
+            .map_err(|err| format!("failed to create a shader module from `{}`: {err}", source.name()))?;
+
+        let compute_entry_point = c"main";
+        let compute_stage = vk::PipelineShaderStageCreateInfo::default()
+            .stage(vk::ShaderStageFlags::COMPUTE)
+            .module(compute_module)
+            .name(compute_entry_point);
+
+        let compute_pipeline_create_infos = [
+            vk::ComputePipelineCreateInfo::default()
+                .stage(compute_stage)
+                .layout(layout)
+        ];
+        let compute_pipelines = unsafe { self.device.create_compute_pipelines(self.pipeline_cache.handle, &compute_pipeline_create_infos, None) };
+
+        unsafe { self.device.destroy_shader_module(compute_module, None); }
+
+        let compute_pipeline = compute_pipelines
+            .map_err(|(_, err)| format!("failed to create a pipeline from `{}`: {err}", source.name()))?
+            .swap_remove(0);
+
+        Ok((compute_pipeline, local_size_x))
+    }
+
+    fn sync_textures<I: Instance>(&self, renderer: &mut Renderer<I>) -> Result<(), Error> {
+        let new_textures = (0_u32..)
+            .zip(&mut renderer.meshes.textures)
+            .skip(renderer.textures.len());
+
+        for (i, texture_data) in new_textures {
+            let texture = self.create_texture(renderer.command_pool, texture_data)?;
+
+            // NOTE: not needed on the cpu anymore
+            texture_data.pixels = Vec::new();
+
+            let image_infos = [
+                vk::DescriptorImageInfo::default()
+                    .sampler(renderer.sampler)
+                    .image_view(texture.2)
+                    .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            ];
+            let descriptor_writes = [
+                vk::WriteDescriptorSet::default()
+                    .dst_set(renderer.descriptor_set)
+                    .dst_binding(0)
+                    .dst_array_element(i)
+                    .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                    .image_info(&image_infos)
+            ];
+            // NOTE: allowed while in use, because of UPDATE_AFTER_BIND and PARTIALLY_BOUND
+            unsafe { self.device.update_descriptor_sets(&descriptor_writes, &[]); }
+
+            renderer.textures.push(texture);
+        }
+
+        Ok(())
+    }
+
+    // NOTE: on errors, whatever was created so far is left for the device to clean up
+    fn create_texture(
+        &self,
+        command_pool: vk::CommandPool,
+        texture_data: &TextureData
+    ) -> Result<(vk::Image, Allocation, vk::ImageView), Error> {
+        let image_extent = vk::Extent3D { width: texture_data.width, height: texture_data.height, depth: 1 };
+
+        let subresource_range = vk::ImageSubresourceRange::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .base_mip_level(0)
+            .level_count(1)
+            .base_array_layer(0)
+            .layer_count(1);
+
+        let image_create_info = vk::ImageCreateInfo::default()
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(vk::Format::R8G8B8A8_SRGB)
+            .extent(image_extent)
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(vk::ImageUsageFlags::SAMPLED | vk::ImageUsageFlags::TRANSFER_DST)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+        let image = unsafe { self.device.create_image(&image_create_info, None) }
+            .map_err(Error::Memory)?;
+
+        let image_memory = self.allocate_image_memory(image)?;
+
+        let (staging, _) = self.create_buffer(
+            &texture_data.pixels,
+            texture_data.pixels.len(),
+            vk::BufferUsageFlags::TRANSFER_SRC
+        )?;
+
+        let submitted = self.one_time_submit(command_pool, |command_buffer| {
+            let transfer_image_memory_barriers = [
+                vk::ImageMemoryBarrier2::default()
+                    .src_stage_mask(vk::PipelineStageFlags2::NONE)
+                    .src_access_mask(vk::AccessFlags2::NONE)
+                    .dst_stage_mask(vk::PipelineStageFlags2::COPY)
+                    .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+                    .old_layout(vk::ImageLayout::UNDEFINED)
+                    .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                    .image(image)
+                    .subresource_range(subresource_range)
+            ];
+            let transfer_dependency_info = vk::DependencyInfo::default()
+                .image_memory_barriers(&transfer_image_memory_barriers);
+            unsafe { self.device.cmd_pipeline_barrier2(command_buffer, &transfer_dependency_info); }
+
+            let regions = [
+                vk::BufferImageCopy::default()
+                    .buffer_offset(0)
+                    .buffer_row_length(0)
+                    .buffer_image_height(0)
+                    .image_subresource(
+                        vk::ImageSubresourceLayers::default()
+                            .aspect_mask(vk::ImageAspectFlags::COLOR)
+                            .mip_level(0)
+                            .base_array_layer(0)
+                            .layer_count(1)
+                    )
+                    .image_extent(image_extent)
+            ];
+            unsafe { self.device.cmd_copy_buffer_to_image(command_buffer, staging.0, image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &regions); }
+
+            let sampling_image_memory_barriers = [
+                vk::ImageMemoryBarrier2::default()
+                    .src_stage_mask(vk::PipelineStageFlags2::COPY)
+                    .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+                    .dst_stage_mask(vk::PipelineStageFlags2::FRAGMENT_SHADER)
+                    .dst_access_mask(vk::AccessFlags2::SHADER_SAMPLED_READ)
+                    .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+                    .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                    .image(image)
+                    .subresource_range(subresource_range)
+            ];
+            let sampling_dependency_info = vk::DependencyInfo::default()
+                .image_memory_barriers(&sampling_image_memory_barriers);
+            unsafe { self.device.cmd_pipeline_barrier2(command_buffer, &sampling_dependency_info); }
+        });
+
+        self.destroy_buffer(staging);
+
+        submitted?;
+
+        let image_view_create_info = vk::ImageViewCreateInfo::default()
+            .image(image)
+            .view_type(vk::ImageViewType::TYPE_2D)
+            .format(vk::Format::R8G8B8A8_SRGB)
+            .subresource_range(subresource_range);
+        let image_view = unsafe { self.device.create_image_view(&image_view_create_info, None) }
+            .map_err(Error::Device)?;
+
+        Ok((image, image_memory, image_view))
+    }
+
+    // records and submits a command buffer, and waits for it to finish
+    fn one_time_submit(
+        &self,
+        command_pool: vk::CommandPool,
+        closure:      impl Fn(vk::CommandBuffer)
+    ) -> Result<(), Error> {
+        let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::default()
+            .command_pool(command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(1);
+        let command_buffers = unsafe { self.device.allocate_command_buffers(&command_buffer_allocate_info) }
+            .map_err(Error::Device)?;
+
+        let command_buffer_infos = [
+            vk::CommandBufferSubmitInfo::default()
+                .command_buffer(command_buffers[0])
+        ];
+        let submit_infos = [
+            vk::SubmitInfo2::default()
+                .command_buffer_infos(&command_buffer_infos)
+        ];
+
+        let submitted = self.with_command_buffer(command_buffers[0], || closure(command_buffers[0]))
+            .and_then(|()| unsafe { self.device.queue_submit2(self.queue, &submit_infos, vk::Fence::null()) }.map_err(Error::Device))
+            .and_then(|()| unsafe { self.device.queue_wait_idle(self.queue) }.map_err(Error::Device));
+
+        unsafe { self.device.free_command_buffers(command_pool, &command_buffers); }
+
+        submitted
+    }
+
+    // everything a renderer owns besides its target and pipelines,
+    // on errors each step destroys what it created, and then calls the step before it
+    fn create_renderer_objects<I: Instance>(
+        &self,
+        frames_in_flight: u32,
+        image_count:      usize,
+        meshes:           &Meshes<I>
+    ) -> Result<RendererObjects<I>, Error> {
+        let sync_objects = self.create_sync_objects(frames_in_flight, image_count)?;
+        let destroy_sync_objects = || unsafe {
+            let (ref image_ready_semaphores, ref render_finished_semaphores, ref in_flight_fences) = sync_objects;
+
+            for fence in in_flight_fences {
+                self.device.destroy_fence(*fence, None);
+            }
+            for semaphore in image_ready_semaphores.iter().chain(render_finished_semaphores) {
+                self.device.destroy_semaphore(*semaphore, None);
+            }
+        };
+
+        let command_pool_create_info = vk::CommandPoolCreateInfo::default()
+            .flags(vk::CommandPoolCreateFlags::TRANSIENT | vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+            .queue_family_index(self.queue_families.graphics);
+        let command_pool = unsafe { self.device.create_command_pool(&command_pool_create_info, None) }
+            .map_err(Error::Device)
+            .inspect_err(|_| destroy_sync_objects())?;
+        // NOTE: also frees the command buffers
+        let destroy_command_pool = || {
+            unsafe { self.device.destroy_command_pool(command_pool, None); }
+
+            destroy_sync_objects();
+        };
+
+        let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::default()
+            .command_pool(command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(frames_in_flight);
+        let command_buffers = unsafe { self.device.allocate_command_buffers(&command_buffer_allocate_info) }
+            .map_err(Error::Device)
+            .inspect_err(|_| destroy_command_pool())?;
+
+        let timestamps = self.create_timestamps(command_pool, frames_in_flight)
+            .inspect_err(|_| destroy_command_pool())?;
+        let destroy_timestamps = || {
+            timestamps.destroy(&self.device);
+
+            destroy_command_pool();
+        };
+
+        let (descriptor_set_layout, descriptor_pool, descriptor_set, sampler) = self.create_texture_descriptors()
+            .inspect_err(|_| destroy_timestamps())?;
+        let destroy_descriptors = || {
+            unsafe {
+                self.device.destroy_sampler(sampler, None);
+                self.device.destroy_descriptor_pool(descriptor_pool, None);
+                self.device.destroy_descriptor_set_layout(descriptor_set_layout, None);
+            }
+
+            destroy_timestamps();
+        };
+
+        let (compute_pipeline_layout, graphics_pipeline_layout) = self.create_pipeline_layouts(descriptor_set_layout)
+            .inspect_err(|_| destroy_descriptors())?;
+
+        let mesh_buffers = self.create_mesh_buffers(command_pool, meshes)
+            .inspect_err(|_| {
+                unsafe {
+                    self.device.destroy_pipeline_layout(compute_pipeline_layout,  None);
+                    self.device.destroy_pipeline_layout(graphics_pipeline_layout, None);
+                }
+
+                destroy_descriptors();
+            })?;
+
+        Ok((
+            sync_objects,
+            (command_pool, command_buffers, timestamps),
+            (descriptor_set_layout, descriptor_pool, descriptor_set, sampler),
+            (compute_pipeline_layout, graphics_pipeline_layout),
+            mesh_buffers
+        ))
+    }
+
+    // NOTE: on errors, the buffers created so far are destroyed
+    fn create_mesh_buffers<I: Instance>(&self, command_pool: vk::CommandPool, meshes: &Meshes<I>) -> Result<MeshBuffers<I>, Error> {
+        let vertices = self.create_static_buffer(
+            command_pool,
+            &meshes.vertices,
+            vk::BufferUsageFlags::VERTEX_BUFFER
+        )?;
+        let indices = self.create_static_buffer(
+            command_pool,
+            &meshes.indices,
+            vk::BufferUsageFlags::INDEX_BUFFER
+        ).inspect_err(|_| self.destroy_buffer(vertices.0))?;
+        let instances = self.create_buffer(
+            &meshes.instances,
+            meshes.instances.len(),
+            vk::BufferUsageFlags::VERTEX_BUFFER
+        ).inspect_err(|_| {
+            self.destroy_buffer(indices.0);
+            self.destroy_buffer(vertices.0);
+        })?;
+
+        Ok((vertices, indices, instances))
+    }
+
+    fn create_sync_objects(&self, frames_in_flight: u32, image_count: usize) -> Result<SyncObjects, Error> {
+        let image_ready_semaphores     = self.create_semaphores(frames_in_flight as usize)?;
+        let render_finished_semaphores = self.create_semaphores(image_count)?;
+
+        let fence_create_info = vk::FenceCreateInfo::default()
+            .flags(vk::FenceCreateFlags::SIGNALED);
+        let in_flight_fences = iter::repeat_with(|| {
+            unsafe { self.device.create_fence(&fence_create_info, None) }
+        }).take(frames_in_flight as usize).collect::<Result<_, _>>().map_err(Error::Device)?;
+
+        Ok((image_ready_semaphores, render_finished_semaphores, in_flight_fences))
+    }
+
+    // NOTE: reset once, so the first frames can check whether they were written yet
+    fn create_timestamps(&self, command_pool: vk::CommandPool, frames_in_flight: u32) -> Result<Timestamps, Error> {
+        let timestamps = Timestamps::new(&self.device, frames_in_flight, self.timestamp_period)?;
+
+        self.one_time_submit(command_pool, |command_buffer| timestamps.reset_all(&self.device, command_buffer))?;
+
+        Ok(timestamps)
+    }
+
+    fn create_semaphores(&self, count: usize) -> Result<Vec<vk::Semaphore>, Error> {
+        let semaphore_create_info = vk::SemaphoreCreateInfo::default();
+
+        iter::repeat_with(|| {
+            unsafe { self.device.create_semaphore(&semaphore_create_info, None) }
+        }).take(count).collect::<Result<_, _>>().map_err(Error::Device)
+    }
+
+    // NOTE: graphics pipelines all share one layout, so they can be swapped between draws
+    #[expect(clippy::unwrap_in_result, reason = "the push constant sizes are small constants")]
+    fn create_pipeline_layouts(&self, descriptor_set_layout: vk::DescriptorSetLayout) -> Result<(vk::PipelineLayout, vk::PipelineLayout), Error> {
+        let compute_push_constant_ranges = [
+            vk::PushConstantRange::default()
+                .stage_flags(vk::ShaderStageFlags::COMPUTE)
+                .offset(0)
+                .size(u32::try_from(compute::PUSH_CONSTANTS_OFFSET + compute::MAX_PUSH_CONSTANTS_SIZE).unwrap())
+        ];
+        let compute_pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::default()
+            .push_constant_ranges(&compute_push_constant_ranges);
+        let compute_pipeline_layout = unsafe { self.device.create_pipeline_layout(&compute_pipeline_layout_create_info, None) }
+            .map_err(Error::Device)?;
+
+        let graphics_push_constant_ranges = [
+            vk::PushConstantRange::default()
+                .stage_flags(vk::ShaderStageFlags::VERTEX)
+                .offset(0)
+                .size(u32::try_from(GRAPHICS_PUSH_CONSTANTS_LEN).unwrap())
+        ];
+        let graphics_set_layouts = [descriptor_set_layout];
+        let graphics_pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::default()
+            .set_layouts(&graphics_set_layouts)
+            .push_constant_ranges(&graphics_push_constant_ranges);
+        let graphics_pipeline_layout = unsafe { self.device.create_pipeline_layout(&graphics_pipeline_layout_create_info, None) }
+            .map_err(Error::Device)?;
+
+        Ok((compute_pipeline_layout, graphics_pipeline_layout))
+    }
+
+    #[expect(clippy::unwrap_in_result, reason = "`MAX_TEXTURES` is a small constant")]
+    fn create_texture_descriptors(&self) -> Result<(vk::DescriptorSetLayout, vk::DescriptorPool, vk::DescriptorSet, vk::Sampler), Error> {
+        let max_textures = u32::try_from(MAX_TEXTURES).unwrap();
+
+        let bindings = [
+            vk::DescriptorSetLayoutBinding::default()
+                .binding(0)
+                .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .descriptor_count(max_textures)
+                .stage_flags(vk::ShaderStageFlags::FRAGMENT)
+        ];
+        let binding_flags = [
+            vk::DescriptorBindingFlags::PARTIALLY_BOUND |
+            vk::DescriptorBindingFlags::UPDATE_AFTER_BIND
+        ];
+        let mut binding_flags_create_info = vk::DescriptorSetLayoutBindingFlagsCreateInfo::default()
+            .binding_flags(&binding_flags);
+        let descriptor_set_layout_create_info = vk::DescriptorSetLayoutCreateInfo::default()
+            .flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL)
+            .bindings(&bindings)
+            .push_next(&mut binding_flags_create_info);
+        let descriptor_set_layout = unsafe { self.device.create_descriptor_set_layout(&descriptor_set_layout_create_info, None) }
+            .map_err(Error::Device)?;
+
+        let pool_sizes = [
+            vk::DescriptorPoolSize::default()
+                .ty(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .descriptor_count(max_textures)
+        ];
+        let descriptor_pool_create_info = vk::DescriptorPoolCreateInfo::default()
+            .flags(vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND)
+            .max_sets(1)
+            .pool_sizes(&pool_sizes);
+        let descriptor_pool = unsafe { self.device.create_descriptor_pool(&descriptor_pool_create_info, None) }
+            .map_err(Error::Device)?;
+
+        let set_layouts = [descriptor_set_layout];
+        let descriptor_set_allocate_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&set_layouts);
+        let descriptor_set = unsafe { self.device.allocate_descriptor_sets(&descriptor_set_allocate_info) }
+            .map_err(Error::Device)?
+            .swap_remove(0);
+
+        let sampler_create_info = vk::SamplerCreateInfo::default()
+            .mag_filter(vk::Filter::LINEAR)
+            .min_filter(vk::Filter::LINEAR)
+            .mipmap_mode(vk::SamplerMipmapMode::NEAREST)
+            .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_w(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .max_lod(vk::LOD_CLAMP_NONE);
+        let sampler = unsafe { self.device.create_sampler(&sampler_create_info, None) }
+            .map_err(Error::Device)?;
+
+        Ok((descriptor_set_layout, descriptor_pool, descriptor_set, sampler))
+    }
+
+    /// # Errors
+    /// when the device is lost
+    #[inline]
+    pub fn device_wait_idle(&self) -> Result<(), Error> {
+        unsafe { self.device.device_wait_idle() }
+            .map_err(Error::Device)
+    }
+
+    /// # Errors
+    /// when the device is lost, or a new swapchain, pipeline, texture or buffer can not be created
+    #[expect(clippy::panic_in_result_fn, reason = "rendering a headless renderer here is a bug, not a runtime failure")]
+    #[inline]
+    pub fn render<I: Instance>(
+        &self,
+        renderer:                 &mut Renderer<I>,
+        winit_pre_present_notify: impl Fn()
+    ) -> Result<(), Error> {
+        assert!(renderer.offscreen.is_none(), "headless renderers are rendered with `Vulkan::render_headless`");
+
+        let fi = renderer.frame_index as usize;
+
+        let in_flight_fence           = renderer.in_flight_fences          [fi];
+        let image_ready_semaphore     = renderer.image_ready_semaphores    [fi];
+        let command_buffer            = renderer.command_buffers           [fi];
+
+        self.wait_for_fence(in_flight_fence)?;
+
+        // NOTE: from the last frame that used this frame index
+        renderer.timestamps.resolve(&self.device, renderer.frame_index)?;
+
+        if renderer.swapchain_outdated {
+            self.recreate_swapchain(renderer)?;
+
+            // NOTE: the window has no area (like when it is minimized),
+            //       nothing is rendered until it is restored
+            if renderer.swapchain_outdated {
+                return Ok(());
+            }
+        }
+
+        self.sync_meshes(renderer)?;
+
+        let Some((image_index, suboptimal)) = self.acquire_next_image(renderer.swapchain, image_ready_semaphore)? else {
+            renderer.swapchain_outdated = true;
+
+            return Ok(());
+        };
+
+        // NOTE: per image, the presentation engine may still be waiting on the one of
+        //       the last frame that presented this image, even when its fence is signaled
+        let render_finished_semaphore = renderer.render_finished_semaphores[image_index as usize];
+
+        // NOTE: only reset once something is going to be submitted,
+        //       so an early return does not leave the next wait hanging
+        self.reset_fence(in_flight_fence)?;
+        self.reset_command_buffer(command_buffer)?;
+
+        self.with_command_buffer(command_buffer, || {
+            renderer.timestamps.reset(&self.device, command_buffer, renderer.frame_index);
+
+            self.record_compute (renderer,              command_buffer);
+            self.record_graphics(renderer, image_index, command_buffer);
+        })?;
+
+        winit_pre_present_notify();
+
+        let outdated = self.submit_and_present(
+            image_ready_semaphore,
+            command_buffer,
+            render_finished_semaphore,
+            in_flight_fence,
+            renderer.swapchain,
+            image_index
+        )?;
+
+        // NOTE: recreated at the start of the next frame
+        renderer.swapchain_outdated = suboptimal || outdated;
+
+        renderer.frame_index = (renderer.frame_index + 1) % renderer.frames_in_flight;
+
+        #[cfg(feature = "validation")]
+        self.check_validation();
+
+        Ok(())
+    }
+
+    /// renders a single frame and reads it back as tightly packed RGBA8 rows
+    ///
+    /// # Errors
+    /// when the device is lost, or a new pipeline, texture or buffer can not be created
+    #[expect(clippy::panic_in_result_fn, reason = "rendering a windowed renderer here is a bug, not a runtime failure")]
+    pub fn render_headless<I: Instance>(&self, renderer: &mut Renderer<I>) -> Result<Vec<u8>, Error> {
+        assert!(renderer.offscreen.is_some(), "only headless renderers can be rendered with `Vulkan::render_headless`");
+
+        let in_flight_fence = renderer.in_flight_fences[0];
+        let command_buffer  = renderer.command_buffers [0];
+
+        self.wait_for_fence(in_flight_fence)?;
+
+        self.sync_meshes(renderer)?;
+
+        self.reset_fence(in_flight_fence)?;
+        self.reset_command_buffer(command_buffer)?;
+
+        let offscreen = renderer.offscreen.as_ref().unwrap();
+
+        self.with_command_buffer(command_buffer, || {
+            renderer.timestamps.reset(&self.device, command_buffer, renderer.frame_index);
+
+            self.record_compute (renderer,    command_buffer);
+            self.record_graphics(renderer, 0, command_buffer);
+
+            let regions = [
+                vk::BufferImageCopy::default()
+                    .buffer_offset(0)
+                    .buffer_row_length(0)
+                    .buffer_image_height(0)
+                    .image_subresource(
+                        vk::ImageSubresourceLayers::default()
+                            .aspect_mask(vk::ImageAspectFlags::COLOR)
+                            .mip_level(0)
+                            .base_array_layer(0)
+                            .layer_count(1)
+                    )
+                    .image_extent(renderer.image_extent.into())
+            ];
+            unsafe { self.device.cmd_copy_image_to_buffer(command_buffer, offscreen.image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, offscreen.readback.0, &regions); }
+
+            // NOTE: makes the copy visible to the host, which reads it after the fence
+            let readback_buffer_memory_barriers = [
+                vk::BufferMemoryBarrier2::default()
+                    .src_stage_mask(vk::PipelineStageFlags2::COPY)
+                    .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+                    .dst_stage_mask(vk::PipelineStageFlags2::HOST)
+                    .dst_access_mask(vk::AccessFlags2::HOST_READ)
+                    .buffer(offscreen.readback.0)
+                    .offset(0)
+                    .size(vk::WHOLE_SIZE)
+            ];
+            let readback_dependency_info = vk::DependencyInfo::default()
+                .buffer_memory_barriers(&readback_buffer_memory_barriers);
+            unsafe { self.device.cmd_pipeline_barrier2(command_buffer, &readback_dependency_info); }
+        })?;
+
+        let command_buffer_infos = [
+            vk::CommandBufferSubmitInfo::default()
+                .command_buffer(command_buffer)
+        ];
+        let submit_infos = [
+            vk::SubmitInfo2::default()
+                .command_buffer_infos(&command_buffer_infos)
+        ];
+        unsafe { self.device.queue_submit2(self.queue, &submit_infos, in_flight_fence) }
+            .map_err(Error::Device)?;
+
+        // NOTE: left signaled, so the next frame does not block on it
+        self.wait_for_fence(in_flight_fence)?;
+
+        renderer.timestamps.resolve(&self.device, renderer.frame_index)?;
+
+        let len = renderer.image_extent.width as usize * renderer.image_extent.height as usize * 4;
+
+        #[cfg(feature = "validation")]
+        self.check_validation();
+
+        Ok(unsafe { slice::from_raw_parts(offscreen.mapped_readback, len) }.to_vec())
+    }
+
+    // NOTE: only panics with `VulkanConfig::panic_on_validation_error`
+    #[cfg(feature = "validation")]
+    fn check_validation(&self) {
+        if let Some(debug_messenger) = self.debug_messenger.as_ref() {
+            debug_messenger.check();
+        }
+    }
+
+    #[inline]
+    fn record_compute<I: Instance>(&self, renderer: &Renderer<I>, command_buffer: vk::CommandBuffer) {
+        let mut push_constants = renderer. vertices_pointer.to_le_bytes().to_vec();
+        push_constants.extend(   renderer.  indices_pointer.to_le_bytes());
+        push_constants.extend(   renderer.instances_pointer.to_le_bytes());
+
+        let cut_off = push_constants.len();
+
+        renderer.timestamps.begin(&self.device, command_buffer, renderer.frame_index, GpuScope::Compute);
+
+        for (i, description) in renderer.meshes.compute_passes.iter().enumerate() {
+            let (compute_pipeline, local_size_x) = renderer.compute_passes[i];
+
+            unsafe { self.device.cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::COMPUTE, compute_pipeline); }
+
+            // (offset, count, group counts) of every dispatch
+            let dispatches = match description.dispatch {
+                Dispatch::Workgroups(group_counts) => vec![(0, 0, group_counts)],
+                Dispatch::Invocations(count)       => vec![(0, count, [count.div_ceil(local_size_x), 1, 1])],
+                Dispatch::Instances(ref key)       => renderer.meshes.instance_datas_per_mesh
+                    .get(key)
+                    .into_iter()
+                    .flatten()
+                    .filter(|instance_data| instance_data.count != 0)
+                    .map(|instance_data| {
+                        let offset = u32::try_from(instance_data.chunk_offset).unwrap();
+                        let count  = u32::try_from(instance_data.count)       .unwrap();
+
+                        (offset, count, [count.div_ceil(local_size_x), 1, 1])
+                    })
+                    .collect()
+            };
+
+            for (offset, count, [group_count_x, group_count_y, group_count_z]) in dispatches {
+                push_constants.truncate(cut_off);
+                push_constants.extend(offset.to_le_bytes());
+                push_constants.extend( count.to_le_bytes());
+                push_constants.extend(&renderer.meshes.compute_push_constants[i]);
+                push_constants.resize(compute::PUSH_CONSTANTS_OFFSET + compute::MAX_PUSH_CONSTANTS_SIZE, 0);
+
+                unsafe {
+                    self.device.cmd_push_constants(command_buffer, renderer.compute_pipeline_layout, vk::ShaderStageFlags::COMPUTE, 0, &push_constants);
+                    self.device.cmd_dispatch(command_buffer, group_count_x, group_count_y, group_count_z);
+                }
+            }
+
+            if description.writes.is_empty() {
+                continue;
+            }
+
+            // NOTE: the following compute passes may read or write them too
+            let buffer_memory_barriers = description.writes
+                .iter()
+                .map(|written| {
+                    let buffer = match *written {
+                        Buffer::Vertices  => renderer.vertices .0,
+                        Buffer::Indices   => renderer.indices  .0,
+                        Buffer::Instances => renderer.instances.0
+                    };
+
+                    vk::BufferMemoryBarrier2::default()
+                        .src_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER)
+                        .src_access_mask(vk::AccessFlags2::SHADER_WRITE)
+                        .dst_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER | vk::PipelineStageFlags2::VERTEX_SHADER)
+                        .dst_access_mask(vk::AccessFlags2::SHADER_READ | vk::AccessFlags2::SHADER_WRITE)
+                        .buffer(buffer)
+                        .offset(0)
+                        .size(vk::WHOLE_SIZE)
+                })
+                .collect::<Vec<_>>();
+            let dependency_info = vk::DependencyInfo::default()
+                .buffer_memory_barriers(&buffer_memory_barriers);
+            unsafe { self.device.cmd_pipeline_barrier2(command_buffer, &dependency_info); }
+        }
+
+        renderer.timestamps.end(&self.device, command_buffer, renderer.frame_index, GpuScope::Compute);
+    }
+
+    #[inline]
+    fn record_graphics<I: Instance>(&self, renderer: &Renderer<I>, image_index: u32, command_buffer: vk::CommandBuffer) {
+        let image = renderer.swapchain_images[image_index as usize];
+
+        let timestamps  = &renderer.timestamps;
+        let frame_index = renderer.frame_index;
+
+        timestamps.begin(&self.device, command_buffer, frame_index, GpuScope::PreRenderBarrier);
+
+        self.with_image_memory_barriers(image, renderer, command_buffer, || {
+            timestamps.end  (&self.device, command_buffer, frame_index, GpuScope::PreRenderBarrier);
+            timestamps.begin(&self.device, command_buffer, frame_index, GpuScope::Rendering);
+
+            self.with_dynamic_rendering(renderer, image_index, command_buffer, || {
+                unsafe {
+                    self.device.cmd_set_viewport(command_buffer, 0, &renderer.viewports);
+                    self.device.cmd_set_scissor(command_buffer, 0, &renderer.scissors);
+                    self.device.cmd_bind_descriptor_sets(command_buffer, vk::PipelineBindPoint::GRAPHICS, renderer.graphics_pipeline_layout, 0, &[renderer.descriptor_set], &[]);
+                }
+
+                renderer.meshes.draw(self, command_buffer, renderer);
+            });
+
+            timestamps.end  (&self.device, command_buffer, frame_index, GpuScope::Rendering);
+            timestamps.begin(&self.device, command_buffer, frame_index, GpuScope::PostRenderBarrier);
+        });
+
+        timestamps.end(&self.device, command_buffer, frame_index, GpuScope::PostRenderBarrier);
+    }
+
+    /// windowed renderers recreate their swapchain at the start of the next frame
+    /// with a non-zero size, until then nothing is rendered
+    ///
+    /// # Errors
+    /// when the device is lost, or the new offscreen image can not be created
+    #[inline]
+    pub fn resize<I: Instance>(&self, renderer: &mut Renderer<I>, width: u32, height: u32) -> Result<(), Error> {
+        if renderer.offscreen.is_some() {
+            self.device_wait_idle()?;
+
+            let (
+                (image_extent, subresource_range, _, swapchain_images, swapchain_image_views, viewports, scissors),
+                offscreen
+            ) = self.create_offscreen_and_everything_related(width, height)?;
+
+            renderer.destroy_swapchain_and_image_views(self);
+            renderer.destroy_offscreen(self);
+
+            renderer.image_extent          = image_extent;
+            renderer.subresource_range     = subresource_range;
+            renderer.swapchain_images      = swapchain_images;
+            renderer.swapchain_image_views = swapchain_image_views;
+            renderer.viewports             = viewports;
+            renderer.scissors              = scissors;
+            renderer.offscreen             = Some(offscreen);
+
+            renderer.camera.set_viewport_size(renderer.viewports[0].width, renderer.viewports[0].height);
+
+            return Ok(());
+        }
+
+        renderer.window_extent      = vk::Extent2D { width, height };
+        renderer.swapchain_outdated = true;
+
+        Ok(())
+    }
+
+    // for the latest window size and present mode,
+    // stays outdated while the surface has no area
+    fn recreate_swapchain<I: Instance>(&self, renderer: &mut Renderer<I>) -> Result<(), Error> {
+        let image_extent = self.surface_extent(renderer.surface, renderer.window_extent)?;
+
+        if image_extent.width == 0 || image_extent.height == 0 {
+            return Ok(());
+        }
+
+        // NOTE: frames in flight may still be using the old swapchain
+        self.device_wait_idle()?;
+
+        let present_mode = self.choose_present_mode(renderer.surface, renderer.requested_present_mode)?;
+
+        let (
+            _,
+            subresource_range,
+            swapchain,
+            swapchain_images,
+            swapchain_image_views,
+            viewports,
+            scissors
+        ) = self.create_swapchain_and_everything_related(
+            renderer.surface,
+            image_extent,
+            renderer.swapchain,
+            present_mode,
+            renderer.surface_format
+        )?;
+
+        renderer.destroy_swapchain_and_image_views(self);
+
+        // NOTE: the surface can hand out a different number of images than before
+        if swapchain_images.len() != renderer.render_finished_semaphores.len() {
+            renderer.destroy_render_finished_semaphores(self);
+            renderer.render_finished_semaphores = self.create_semaphores(swapchain_images.len())?;
+        }
+
+        renderer.image_extent          = image_extent;
+        renderer.subresource_range     = subresource_range;
+        renderer.swapchain             = swapchain;
+        renderer.swapchain_images      = swapchain_images;
+        renderer.swapchain_image_views = swapchain_image_views;
+        renderer.viewports             = viewports;
+        renderer.scissors              = scissors;
+        renderer.present_mode          = present_mode;
+        renderer.swapchain_outdated    = false;
+
+        renderer.camera.set_viewport_size(renderer.viewports[0].width, renderer.viewports[0].height);
+
+        Ok(())
+    }
+
+    // the window size, unless the surface decides it
+    fn surface_extent(&self, surface: vk::SurfaceKHR, window_extent: vk::Extent2D) -> Result<vk::Extent2D, Error> {
+        let surface_capabilities = unsafe { self.ext_surface.get_physical_device_surface_capabilities(self.physical_device, surface) }
+            .map_err(Error::Surface)?;
+
+        if surface_capabilities.current_extent.width != u32::MAX {
+            return Ok(surface_capabilities.current_extent);
+        }
+
+        let min = surface_capabilities.min_image_extent;
+        let max = surface_capabilities.max_image_extent;
+
+        Ok(vk::Extent2D {
+            width:  window_extent.width .clamp(min.width,  max.width),
+            height: window_extent.height.clamp(min.height, max.height)
+        })
+    }
+
+    // NOTE: chosen once per renderer, the pipelines are created for its format
+    fn choose_surface_format(&self, surface: vk::SurfaceKHR, requested: ColorSpace) -> Result<(ColorSpace, vk::SurfaceFormatKHR), Error> {
+        let supported = unsafe { self.ext_surface.get_physical_device_surface_formats(self.physical_device, surface) }
+            .map_err(Error::Surface)?;
+
+        // NOTE: anything else would be presented with the wrong gamma or color space
+        let chosen = requested
+            .choose(&supported)
+            .ok_or(Error::Surface(vk::Result::ERROR_FORMAT_NOT_SUPPORTED))?;
+
+        // NOTE: the other fallbacks only lose range, this one is presented too dark unless the shaders encode
+        if chosen.0 == ColorSpace::SrgbUnorm && requested != ColorSpace::SrgbUnorm {
+            log::warn!(target: "dacho", "the surface has no sRGB format, falling back to `ColorSpace::SrgbUnorm`, the shaders have to sRGB encode their output");
+        }
+
+        Ok(chosen)
+    }
+
+    fn choose_present_mode(&self, surface: vk::SurfaceKHR, requested: PresentMode) -> Result<PresentMode, Error> {
+        let supported = unsafe { self.ext_surface.get_physical_device_surface_present_modes(self.physical_device, surface) }
+            .map_err(Error::Surface)?;
+
+        Ok(requested.choose(&supported))
+    }
+
+    #[inline]
+    fn create_swapchain_and_everything_related(
+        &self,
+        surface:        vk::SurfaceKHR,
+        image_extent:   vk::Extent2D,
+        old_swapchain:  vk::SwapchainKHR,
+        present_mode:   PresentMode,
+        surface_format: vk::SurfaceFormatKHR
+    ) -> Result<SwapchainAndEverythingRelated, Error> {
+        let subresource_range = vk::ImageSubresourceRange::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .base_mip_level(0)
+            .level_count(1)
+            .base_array_layer(0)
+            .layer_count(1);
+
+        let surface_capabilities = unsafe { self.ext_surface.get_physical_device_surface_capabilities(self.physical_device, surface) }
+            .map_err(Error::Surface)?;
+
+        // NOTE: one more than needed, so there is always an image to render to,
+        //       a max_image_count of 0 means there is no limit
+        let min_image_count = if surface_capabilities.max_image_count == 0 {
+            surface_capabilities.min_image_count + 1
+        } else {
+            (surface_capabilities.min_image_count + 1).min(surface_capabilities.max_image_count)
+        };
+
+        // NOTE: shared concurrently when presenting from another family,
+        //       instead of transferring ownership of the image every frame
+        let queue_family_indices = self.queue_families.unique();
+        let image_sharing_mode   = if queue_family_indices.len() > 1 {
+            vk::SharingMode::CONCURRENT
+        } else {
+            vk::SharingMode::EXCLUSIVE
+        };
+
+        let swapchain_create_info = vk::SwapchainCreateInfoKHR::default()
+            .old_swapchain(old_swapchain)
+            .image_sharing_mode(image_sharing_mode)
+            .queue_family_indices(&queue_family_indices)
+            .surface(surface)
+            .image_format(surface_format.format)
+            .image_color_space(surface_format.color_space)
+            .image_extent(image_extent)
+            .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
+            .image_array_layers(1)
+            .min_image_count(min_image_count)
+            .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
+            .pre_transform(surface_capabilities.current_transform)
+            .clipped(true)
+            .present_mode(present_mode.to_vk());
+        let swapchain = unsafe { self.ext_swapchain.create_swapchain(&swapchain_create_info, None) }
+            .map_err(Error::Swapchain)?;
+
+        let swapchain_images = unsafe { self.ext_swapchain.get_swapchain_images(swapchain) }
+            .map_err(Error::Swapchain)?;
+
+        let swapchain_image_views = swapchain_images
+            .iter()
+            .map(|image| {
+                let image_view_create_info = vk::ImageViewCreateInfo::default()
+                    .image(*image)
+                    .view_type(vk::ImageViewType::TYPE_2D)
+                    .format(surface_format.format)
+                    .subresource_range(subresource_range);
+
+                let image_view = unsafe { self.device.create_image_view(&image_view_create_info, None) }
+                    .map_err(Error::Device);
+
+                image_view
+            })
+            .collect::<Result<_, _>>()?;
+
+        let viewports = [
+            vk::Viewport {
+                x:         0.0,                       y:         0.0,
+                width:     image_extent.width  as f32, height:    image_extent.height as f32,
+                min_depth: 0.0,                       max_depth: 1.0
+            }
+        ];
+        let scissors = [image_extent.into()];
+
+        Ok((
+            image_extent,
+            subresource_range,
+            swapchain,
+            swapchain_images,
+            swapchain_image_views,
+            viewports,
+            scissors
+        ))
+    }
+
+    #[inline]
+    fn create_offscreen_and_everything_related(
+        &self,
+        width:  u32,
+        height: u32
+    ) -> Result<(SwapchainAndEverythingRelated, Offscreen), Error> {
+        let image_extent = vk::Extent2D { width, height };
 
-                let image_view = unsafe { self.device.create_image_view(&image_view_create_info, None) }
-                    .unwrap();
+        let subresource_range = vk::ImageSubresourceRange::default()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .base_mip_level(0)
+            .level_count(1)
+            .base_array_layer(0)
+            .layer_count(1);
 
-                image_view
-            })
-            .collect();
+        let image_create_info = vk::ImageCreateInfo::default()
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(SRGB_SURFACE_FORMAT.format)
+            .extent(image_extent.into())
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+        let image = unsafe { self.device.create_image(&image_create_info, None) }
+            .map_err(Error::Memory)?;
+
+        let image_memory = self.allocate_image_memory(image)?;
+
+        let image_view_create_info = vk::ImageViewCreateInfo::default()
+            .image(image)
+            .view_type(vk::ImageViewType::TYPE_2D)
+            .format(SRGB_SURFACE_FORMAT.format)
+            .subresource_range(subresource_range);
+        let image_view = unsafe { self.device.create_image_view(&image_view_create_info, None) }
+            .map_err(Error::Device)?;
+
+        // RGBA8
+        let pixels                     = vec![0_u8; width as usize * height as usize * 4];
+        let (readback, mapped_readback) = self.create_buffer(
+            &pixels,
+            pixels.len(),
+            vk::BufferUsageFlags::TRANSFER_DST
+        )?;
 
         let viewports = [
             vk::Viewport {
@@ -540,44 +2181,57 @@ impl Vulkan {
         ];
         let scissors = [image_extent.into()];
 
-        (
-            image_extent,
-            subresource_range,
-            swapchain,
-            swapchain_images,
-            swapchain_image_views,
-            viewports,
-            scissors,
-            max_frames_in_flight
-        )
+        let offscreen = Offscreen {
+            image,
+            image_memory,
+            readback,
+            mapped_readback: mapped_readback.cast_const()
+        };
+
+        Ok((
+            (
+                image_extent,
+                subresource_range,
+                vk::SwapchainKHR::null(),
+                vec![image],
+                vec![image_view],
+                viewports,
+                scissors
+            ),
+            offscreen
+        ))
     }
 
     #[inline]
-    fn wait_for_and_reset_fences(&self, in_flight_fence: vk::Fence) {
-        let in_flight_fences = [in_flight_fence];
+    fn wait_for_fence(&self, in_flight_fence: vk::Fence) -> Result<(), Error> {
+        unsafe { self.device.wait_for_fences(&[in_flight_fence], true, u64::MAX) }
+            .map_err(Error::Device)
+    }
 
-        unsafe { self.device.wait_for_fences(&in_flight_fences, true, u64::MAX) }
-            .unwrap();
-        unsafe { self.device.reset_fences(&in_flight_fences) }
-            .unwrap();
+    #[inline]
+    fn reset_fence(&self, in_flight_fence: vk::Fence) -> Result<(), Error> {
+        unsafe { self.device.reset_fences(&[in_flight_fence]) }
+            .map_err(Error::Device)
     }
 
     #[inline]
-    fn reset_command_buffer(&self, command_buffer: vk::CommandBuffer) {
+    fn reset_command_buffer(&self, command_buffer: vk::CommandBuffer) -> Result<(), Error> {
         unsafe { self.device.reset_command_buffer(command_buffer, vk::CommandBufferResetFlags::RELEASE_RESOURCES) }
-            .unwrap();
+            .map_err(Error::Device)
     }
 
+    // with whether the swapchain is suboptimal, None when it is out of date
     #[inline]
     fn acquire_next_image(
         &self,
         swapchain:             vk::SwapchainKHR,
         image_ready_semaphore: vk::Semaphore
-    ) -> u32 {
-        let (image_index, _) = unsafe { self.ext_swapchain.acquire_next_image(swapchain, u64::MAX, image_ready_semaphore, vk::Fence::null()) }
-            .unwrap();
-
-        image_index
+    ) -> Result<Option<(u32, bool)>, Error> {
+        match unsafe { self.ext_swapchain.acquire_next_image(swapchain, u64::MAX, image_ready_semaphore, vk::Fence::null()) } {
+            Ok(acquired)                           => Ok(Some(acquired)),
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(None),
+            Err(err)                               => Err(Error::Swapchain(err))
+        }
     }
 
     #[inline]
@@ -585,23 +2239,23 @@ impl Vulkan {
         &self,
         command_buffer: vk::CommandBuffer,
         closure:        impl Fn()
-    ) {
+    ) -> Result<(), Error> {
         let begin_info = vk::CommandBufferBeginInfo::default()
             .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
         unsafe { self.device.begin_command_buffer(command_buffer, &begin_info) }
-            .unwrap();
+            .map_err(Error::Device)?;
 
         closure();
 
         unsafe { self.device.end_command_buffer(command_buffer) }
-            .unwrap();
+            .map_err(Error::Device)
     }
 
     #[inline]
-    fn with_image_memory_barriers(
+    fn with_image_memory_barriers<I: Instance>(
         &self,
         image:          vk::Image,
-        renderer:       &Renderer,
+        renderer:       &Renderer<I>,
         command_buffer: vk::CommandBuffer,
         closure:        impl Fn()
     ) {
@@ -622,14 +2276,21 @@ impl Vulkan {
 
         closure();
 
+        // NOTE: offscreen images get copied into the readback buffer instead of presented
+        let (dst_stage_mask, dst_access_mask, new_layout) = if renderer.offscreen.is_some() {
+            (vk::PipelineStageFlags2::COPY,           vk::AccessFlags2::TRANSFER_READ, vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+        } else {
+            (vk::PipelineStageFlags2::BOTTOM_OF_PIPE, vk::AccessFlags2::NONE,          vk::ImageLayout::PRESENT_SRC_KHR)
+        };
+
         let presenting_image_memory_barriers = [
             vk::ImageMemoryBarrier2::default()
                 .src_stage_mask(vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
                 .src_access_mask(vk::AccessFlags2::COLOR_ATTACHMENT_WRITE)
-                .dst_stage_mask(vk::PipelineStageFlags2::BOTTOM_OF_PIPE)
-                .dst_access_mask(vk::AccessFlags2::NONE)
+                .dst_stage_mask(dst_stage_mask)
+                .dst_access_mask(dst_access_mask)
                 .old_layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
-                .new_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+                .new_layout(new_layout)
                 .image(image)
                 .subresource_range(renderer.subresource_range)
         ];
@@ -639,9 +2300,9 @@ impl Vulkan {
     }
 
     #[inline]
-    fn with_dynamic_rendering(
+    fn with_dynamic_rendering<I: Instance>(
         &self,
-        renderer:       &Renderer,
+        renderer:       &Renderer<I>,
         image_index:    u32,
         command_buffer: vk::CommandBuffer,
         closure:        impl Fn()
@@ -666,6 +2327,7 @@ impl Vulkan {
         unsafe { self.device.cmd_end_rendering(command_buffer); }
     }
 
+    // whether the swapchain is suboptimal or out of date
     #[inline]
     fn submit_and_present(
         &self,
@@ -675,7 +2337,7 @@ impl Vulkan {
         in_flight_fence:           vk::Fence,
         swapchain:                 vk::SwapchainKHR,
         image_index:               u32
-    ) {
+    ) -> Result<bool, Error> {
         let render_finished_semaphores = [render_finished_semaphore];
         let swapchains                 = [swapchain];
         let image_indices              = [image_index];
@@ -703,27 +2365,42 @@ impl Vulkan {
                 .signal_semaphore_infos(&render_finished_semaphore_infos)
         ];
         unsafe { self.device.queue_submit2(self.queue, &submit_infos, in_flight_fence) }
-            .unwrap();
+            .map_err(Error::Device)?;
 
         let present_info = vk::PresentInfoKHR::default()
             .wait_semaphores(&render_finished_semaphores)
             .swapchains(&swapchains)
             .image_indices(&image_indices);
-        unsafe { self.ext_swapchain.queue_present(self.queue, &present_info) }
-            .unwrap();
+        match unsafe { self.ext_swapchain.queue_present(self.present_queue, &present_info) } {
+            Ok(suboptimal)                         => Ok(suboptimal),
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(true),
+            Err(err)                               => Err(Error::Swapchain(err))
+        }
     }
 }
 
 impl Drop for Vulkan {
     fn drop(&mut self) {
+        self.allocator
+            .get_mut()
+            .destroy(&self.device);
+
+        self.pipeline_cache.save_and_destroy(&self.device);
+
+        unsafe { self.device.destroy_device(None); }
+
+        #[cfg(feature = "validation")]
+        if let Some(debug_messenger) = self.debug_messenger.as_ref() {
+            debug_messenger.destroy();
+        }
+
         unsafe {
-            self.device  .destroy_device(None);
             self.instance.destroy_instance(None);
         }
     }
 }
 
-pub struct Renderer {
+pub struct Renderer<I: Instance = [f32; 2]> {
         surface:                    vk::SurfaceKHR,
         image_extent:               vk::Extent2D,
         swapchain:                  vk::SwapchainKHR,
@@ -738,215 +2415,274 @@ pub struct Renderer {
         viewports:                  [vk::Viewport; 1],
         scissors:                   [vk::Rect2D;   1],
         compute_pipeline_layout:    vk::PipelineLayout,
-        compute_pipeline:           vk::Pipeline,
+        // with the local_size_x of their compute shader
+        compute_passes:             Vec<(vk::Pipeline, u32)>,
         graphics_pipeline_layout:   vk::PipelineLayout,
-        graphics_pipeline:          vk::Pipeline,
+        // with the vertex stride in their vertex shader, if it was found
+        pipelines:                  Vec<(vk::Pipeline, Option<usize>)>,
         clear_value:                vk::ClearValue,
         frame_index:                u32,
-        max_frames_in_flight:       u32,
-        vertices:                   (vk::Buffer, vk::DeviceMemory),
-        indices:                    (vk::Buffer, vk::DeviceMemory),
-        instances:                  (vk::Buffer, vk::DeviceMemory),
-    pub meshes:                     Meshes,
+        frames_in_flight:           u32,
+        vertices:                   (vk::Buffer, Allocation),
+        indices:                    (vk::Buffer, Allocation),
+        instances:                  (vk::Buffer, Allocation),
+    pub meshes:                     Meshes<I>,
+    pub camera:                     Camera2D,
         vertices_pointer:           u64,
         indices_pointer:            u64,
         instances_pointer:          u64,
-        mapped_instances_dst:       *mut f32
+        mapped_instances_dst:       *mut I,
+        vertices_len:               usize,
+        indices_len:                usize,
+        instances_capacity:         usize,
+        offscreen:                  Option<Offscreen>,
+        timestamps:                 Timestamps,
+        // the latest size from `Vulkan::resize`, the swapchain catches up at the next frame
+        window_extent:              vk::Extent2D,
+        swapchain_outdated:         bool,
+        // the one in use can differ from the requested one, see `PresentMode`
+        requested_present_mode:     PresentMode,
+        present_mode:               PresentMode,
+        // the one in use, with its format
+        color_space:                ColorSpace,
+        surface_format:             vk::SurfaceFormatKHR,
+        descriptor_set_layout:      vk::DescriptorSetLayout,
+        descriptor_pool:            vk::DescriptorPool,
+        descriptor_set:             vk::DescriptorSet,
+        sampler:                    vk::Sampler,
+        textures:                   Vec<(vk::Image, Allocation, vk::ImageView)>,
+    #[cfg(feature = "hot_reload")]
+        shader_watcher:             ShaderWatcher
 }
 
-impl Renderer {
+impl<I: Instance> Renderer<I> {
+    // NOTE: the swapchain is rebuilt at the start of the next frame
     #[inline]
-    pub fn update_instance(&mut self, handle: &InstanceHandle, value: [f32; INSTANCE_SIZE]) {
-        let offset: usize = handle.0.get();
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) {
+        if present_mode == self.requested_present_mode {
+            return;
+        }
+
+        self.requested_present_mode = present_mode;
+        self.swapchain_outdated     = true;
+    }
+
+    // the one in use, which can be a fallback of the requested one
+    #[must_use]
+    #[inline]
+    pub const fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
 
-        let src = value.as_ptr();
-        let dst = self.mapped_instances_dst.wrapping_add(offset);
-        unsafe { ptr::copy_nonoverlapping(src, dst, INSTANCE_SIZE); }
+    // how long each scope took on the gpu, in the last finished frame,
+    // empty if the gpu can not measure it, or before the first frame finished
+    #[must_use]
+    #[inline]
+    pub fn gpu_timings(&self) -> &[GpuTiming] {
+        self.timestamps.timings()
     }
 
+    // the one in use, which can be a fallback of the requested one,
+    // the shaders have to output colors in it
     #[must_use]
+    #[inline]
+    pub const fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    // NOTE: the vertex and index buffers are reuploaded at the start of the next frame,
+    //       and meshes are drawn in the order they are registered
+    #[inline]
+    pub fn register<M: Mesh>(&mut self, pipeline: Pipeline, instance_count_estimate: usize) {
+        self.meshes.register::<M>(pipeline, instance_count_estimate);
+        self.check_vertex_strides();
+    }
+
+    // NOTE: created at the start of the next frame
+    #[inline]
+    pub fn add_pipeline(&mut self, description: PipelineDescription) -> Pipeline {
+        self.meshes.add_pipeline(description)
+    }
+
+    // NOTE: created at the start of the next frame
+    #[inline]
+    pub fn add_compute_pass(&mut self, description: ComputePassDescription) -> ComputePass {
+        self.meshes.add_compute_pass(description)
+    }
+
+    #[inline]
+    pub fn set_push_constants<P: PushConstants>(&mut self, pass: ComputePass, value: &P) {
+        self.meshes.set_push_constants(pass, value);
+    }
+
+    // NOTE: uploaded at the start of the next frame
+    #[inline]
+    pub fn add_texture(&mut self, width: u32, height: u32, pixels: Vec<u8>) -> Texture {
+        self.meshes.add_texture(width, height, pixels)
+    }
+
+    #[cfg(feature = "png")]
+    #[inline]
+    pub fn load_texture(&mut self, path: impl AsRef<Path>) -> Option<Texture> {
+        self.meshes.load_texture(path)
+    }
+
+    // NOTE: uploaded at the start of the next frame
+    #[cfg(feature = "text")]
+    #[inline]
+    pub fn add_font(&mut self, font: &Font) -> Texture {
+        self.meshes.add_font(font)
+    }
+
+    // NOTE: replace text by removing the returned instances, and adding it again
+    #[cfg(feature = "text")]
+    #[inline]
+    pub fn add_text<M: Mesh>(
+        &mut self,
+        font:        &Font,
+        text:        &str,
+        origin:      [f32; 2],
+        style:       &TextStyle,
+        to_instance: impl FnMut(Glyph) -> I
+    ) -> Vec<InstanceHandle<I>> {
+        font
+            .instances(text, origin, style, to_instance)
+            .map(|instance| self.add_instance::<M>(instance))
+            .collect()
+    }
+
+    #[inline]
+    pub fn add_instance<M: Mesh>(&mut self, value: I) -> InstanceHandle<I> {
+        let handle = self.meshes.add_instance::<M>(value);
+
+        self.update_instance(&handle, value);
+
+        handle
+    }
+
+    #[inline]
+    pub fn update_instance(&mut self, handle: &InstanceHandle<I>, value: I) {
+        let offset: usize = handle.0.get();
+
+        let dst = self.instance_ptr(offset);
+        unsafe { dst.write(value); }
+    }
+
+    #[inline]
+    pub fn remove_instance(&mut self, handle: InstanceHandle<I>) {
+        let Some((from, to)) = self.meshes.remove_instance(handle) else {
+            return;
+        };
+
+        // NOTE: the mapped buffer is the source of truth here,
+        //       it also holds the changes from Self::update_instance and compute
+        let src = self.instance_ptr(from).cast_const();
+        let dst = self.instance_ptr(to);
+        unsafe { ptr::copy_nonoverlapping(src, dst, 1); }
+    }
+
+    #[inline]
+    const fn instance_ptr(&mut self, offset: usize) -> *mut I {
+        if offset < self.instances_capacity {
+            self.mapped_instances_dst.wrapping_add(offset)
+        } else {
+            // NOTE: does not fit in the buffer yet, gets uploaded with the next Vulkan::sync_meshes
+            self.meshes.instances.as_mut_ptr().wrapping_add(offset)
+        }
+    }
+
+    #[expect(clippy::panic_in_result_fn, reason = "a windowed renderer from a headless `Vulkan`, or zero frames in flight, is a bug, not a runtime failure")]
     fn new(
         vk:          &Vulkan,
         handle:      impl HasDisplayHandle + HasWindowHandle,
         width:       u32,
         height:      u32,
         clear_color: [f32; 4],
-        meshes:      Meshes
-    ) -> Self {
+        meshes:      Meshes<I>,
+        config:      &RendererConfig
+    ) -> Result<Self, Error> {
+        assert!(vk.queue_families.present.is_some(), "`Vulkan::new_headless` can only create headless renderers, use `Vulkan::new` with the window");
+        assert!(config.frames_in_flight != 0,         "`RendererConfig::frames_in_flight` can not be zero");
+
         let rdh = handle
             .display_handle()
-            .unwrap()
+            .map_err(Error::WindowHandle)?
             .into();
         let rwh = handle
             .window_handle()
-            .unwrap()
+            .map_err(Error::WindowHandle)?
             .into();
         let surface = unsafe { ash_window::create_surface(&vk.entry, &vk.instance, rdh, rwh, None) }
-            .unwrap();
-
-        let (
-            image_extent,
-            subresource_range,
-            swapchain,
-            swapchain_images,
-            swapchain_image_views,
-            viewports,
-            scissors,
-            max_frames_in_flight
-        ) = vk.create_swapchain_and_everything_related(surface, width, height, vk::SwapchainKHR::null());
+            .map_err(Error::Surface)?;
 
-        let semaphore_create_info = vk::SemaphoreCreateInfo::default();
-        let image_ready_semaphores = iter::repeat_with(|| {
-            unsafe { vk.device.create_semaphore(&semaphore_create_info, None) }
-                .unwrap()
-        }).take(max_frames_in_flight as usize).collect();
-        let render_finished_semaphores = iter::repeat_with(|| {
-            unsafe { vk.device.create_semaphore(&semaphore_create_info, None) }
-                .unwrap()
-        }).take(max_frames_in_flight as usize).collect();
-
-        let fence_create_info = vk::FenceCreateInfo::default()
-            .flags(vk::FenceCreateFlags::SIGNALED);
-        let in_flight_fences = iter::repeat_with(|| {
-            unsafe { vk.device.create_fence(&fence_create_info, None) }
-                .unwrap()
-        }).take(max_frames_in_flight as usize).collect();
+        let window_extent = vk::Extent2D { width, height };
 
-        let command_pool_create_info = vk::CommandPoolCreateInfo::default()
-            .flags(vk::CommandPoolCreateFlags::TRANSIENT | vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
-            .queue_family_index(0);
-        let command_pool = unsafe { vk.device.create_command_pool(&command_pool_create_info, None) }
-            .unwrap();
+        let (present_mode, (color_space, surface_format), swapchain_and_everything_related) = vk.surface_extent(surface, window_extent)
+            .and_then(|image_extent| {
+                let present_mode = vk.choose_present_mode  (surface, config.present_mode)?;
+                let chosen       = vk.choose_surface_format(surface, config.color_space )?;
 
-        let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::default()
-            .command_pool(command_pool)
-            .level(vk::CommandBufferLevel::PRIMARY)
-            .command_buffer_count(max_frames_in_flight);
-        let command_buffers = unsafe { vk.device.allocate_command_buffers(&command_buffer_allocate_info) }
-            .unwrap();
+                vk.create_swapchain_and_everything_related(surface, image_extent, vk::SwapchainKHR::null(), present_mode, chosen.1)
+                    .map(|swapchain_and_everything_related| (present_mode, chosen, swapchain_and_everything_related))
+            })
+            .inspect_err(|_| unsafe { vk.ext_surface.destroy_surface(surface, None); })?;
 
-        let compute_code   = read_spirv("examples/usage/assets/shaders/test/comp.glsl");
-        let compute_module_create_info = vk::ShaderModuleCreateInfo::default()
-            .code(&compute_code);
-        let compute_module = unsafe { vk.device.create_shader_module(&compute_module_create_info, None) }
-            .unwrap();
+        let mut renderer = Self::with_target(vk, surface, swapchain_and_everything_related, None, clear_color, meshes, config.frames_in_flight)?;
 
-        let compute_entry_point = c"main";
-        let compute_stage = vk::PipelineShaderStageCreateInfo::default()
-            .stage(vk::ShaderStageFlags::COMPUTE)
-            .module(compute_module)
-            .name(compute_entry_point);
-        let push_constant_ranges = [
-            vk::PushConstantRange::default()
-                .stage_flags(vk::ShaderStageFlags::COMPUTE)
-                .offset(0)
-                .size(u32::try_from(PUSH_CONSTANTS_LEN).unwrap())
-        ];
-        let compute_pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::default()
-            .push_constant_ranges(&push_constant_ranges);
-        let compute_pipeline_layout = unsafe { vk.device.create_pipeline_layout(&compute_pipeline_layout_create_info, None) }
-            .unwrap();
+        renderer.window_extent          = window_extent;
+        renderer.requested_present_mode = config.present_mode;
+        renderer.present_mode           = present_mode;
+        renderer.color_space            = color_space;
+        renderer.surface_format         = surface_format;
 
-        let compute_pipeline_create_infos = [
-            vk::ComputePipelineCreateInfo::default()
-                .stage(compute_stage)
-                .layout(compute_pipeline_layout)
-        ];
-        let compute_pipeline = unsafe { vk.device.create_compute_pipelines(vk::PipelineCache::null(), &compute_pipeline_create_infos, None) }
-            .unwrap()
-            .swap_remove(0);
+        Ok(renderer)
+    }
 
-        unsafe { vk.device.destroy_shader_module(compute_module, None); }
+    fn new_headless(
+        vk:          &Vulkan,
+        width:       u32,
+        height:      u32,
+        clear_color: [f32; 4],
+        meshes:      Meshes<I>
+    ) -> Result<Self, Error> {
+        let (swapchain_and_everything_related, offscreen) = vk.create_offscreen_and_everything_related(width, height)?;
 
-        let vertex_code   = read_spirv("examples/usage/assets/shaders/test/vert.glsl");
-        let fragment_code = read_spirv("examples/usage/assets/shaders/test/frag.glsl");
-        let vertex_module_create_info = vk::ShaderModuleCreateInfo::default()
-            .code(&vertex_code);
-        let vertex_module = unsafe { vk.device.create_shader_module(&vertex_module_create_info, None) }
-            .unwrap();
-        let fragment_module_create_info = vk::ShaderModuleCreateInfo::default()
-            .code(&fragment_code);
-        let fragment_module = unsafe { vk.device.create_shader_module(&fragment_module_create_info, None) }
-            .unwrap();
+        // NOTE: every frame is waited for, see `Vulkan::render_headless`
+        Self::with_target(vk, vk::SurfaceKHR::null(), swapchain_and_everything_related, Some(offscreen), clear_color, meshes, 1)
+    }
 
-        let graphics_entry_point = c"main";
-        let graphics_stages = [
-            vk::PipelineShaderStageCreateInfo::default()
-                .stage(vk::ShaderStageFlags::VERTEX)
-                .module(vertex_module)
-                .name(graphics_entry_point),
-            vk::PipelineShaderStageCreateInfo::default()
-                .stage(vk::ShaderStageFlags::FRAGMENT)
-                .module(fragment_module)
-                .name(graphics_entry_point)
-        ];
-        let vertex_input_state = vk::PipelineVertexInputStateCreateInfo::default()
-            .vertex_binding_descriptions(&[])
-            .vertex_attribute_descriptions(&[]);
-        let input_assembly_state = vk::PipelineInputAssemblyStateCreateInfo::default()
-            .primitive_restart_enable(false)
-            .topology(vk::PrimitiveTopology::TRIANGLE_LIST);
-        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
-            .viewport_count(1)
-            .scissor_count(1);
-        let rasterization_state = vk::PipelineRasterizationStateCreateInfo::default()
-            .rasterizer_discard_enable(false)
-            .polygon_mode(vk::PolygonMode::FILL)
-            .cull_mode(vk::CullModeFlags::BACK)
-            .front_face(vk::FrontFace::COUNTER_CLOCKWISE)
-            .depth_bias_enable(false)
-            .line_width(1.0);
-        let multisample_state = vk::PipelineMultisampleStateCreateInfo::default()
-            .rasterization_samples(vk::SampleCountFlags::TYPE_1);
-        let color_blend_attachments = [
-            vk::PipelineColorBlendAttachmentState::default()
-                .blend_enable(false)
-                .color_write_mask(vk::ColorComponentFlags::RGBA)
-        ];
-        let depth_stencil_state = vk::PipelineDepthStencilStateCreateInfo::default()
-            .depth_test_enable(false)
-            .depth_write_enable(false)
-            .depth_compare_op(vk::CompareOp::LESS_OR_EQUAL)
-            .depth_bounds_test_enable(false)
-            .stencil_test_enable(false);
-        let color_blend_state = vk::PipelineColorBlendStateCreateInfo::default()
-            .logic_op_enable(false)
-            .attachments(&color_blend_attachments);
-        let dynamic_state = vk::PipelineDynamicStateCreateInfo::default()
-            .dynamic_states(&[vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR]);
-        let graphics_push_constant_ranges = [
-            vk::PushConstantRange::default()
-                .stage_flags(vk::ShaderStageFlags::VERTEX)
-                .offset(0)
-                .size(u32::try_from(PUSH_CONSTANTS_LEN).unwrap())
-        ];
-        let graphics_pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::default()
-            .push_constant_ranges(&graphics_push_constant_ranges);
-        let graphics_pipeline_layout = unsafe { vk.device.create_pipeline_layout(&graphics_pipeline_layout_create_info, None) }
-            .unwrap();
+    // NOTE: takes over the surface and the swapchain or offscreen image,
+    //       on errors they are destroyed along with everything created so far
+    fn with_target(
+        vk:                               &Vulkan,
+        surface:                          vk::SurfaceKHR,
+        swapchain_and_everything_related: SwapchainAndEverythingRelated,
+        offscreen:                        Option<Offscreen>,
+        clear_color:                      [f32; 4],
+        meshes:                           Meshes<I>,
+        frames_in_flight:                 u32
+    ) -> Result<Self, Error> {
+        let (
+            image_extent,
+            subresource_range,
+            swapchain,
+            swapchain_images,
+            swapchain_image_views,
+            viewports,
+            scissors
+        ) = swapchain_and_everything_related;
 
-        let mut rendering_info = vk::PipelineRenderingCreateInfo::default()
-            .color_attachment_formats(&[SWAPCHAIN_FORMAT]);
-        let graphics_pipeline_create_infos = [
-            vk::GraphicsPipelineCreateInfo::default()
-                .stages(&graphics_stages)
-                .vertex_input_state(&vertex_input_state)
-                .input_assembly_state(&input_assembly_state)
-                .viewport_state(&viewport_state)
-                .rasterization_state(&rasterization_state)
-                .multisample_state(&multisample_state)
-                .depth_stencil_state(&depth_stencil_state)
-                .color_blend_state(&color_blend_state)
-                .dynamic_state(&dynamic_state)
-                .layout(graphics_pipeline_layout)
-                .push_next(&mut rendering_info)
-        ];
-        let graphics_pipeline = unsafe { vk.device.create_graphics_pipelines(vk::PipelineCache::null(), &graphics_pipeline_create_infos, None) }
-            .unwrap()
-            .swap_remove(0);
+        let (
+            (image_ready_semaphores, render_finished_semaphores, in_flight_fences),
+            (command_pool, command_buffers, timestamps),
+            (descriptor_set_layout, descriptor_pool, descriptor_set, sampler),
+            (compute_pipeline_layout, graphics_pipeline_layout),
+            ((vertices, vertices_pointer), (indices, indices_pointer), (instances, mapped_instances_dst))
+        ) = vk.create_renderer_objects(frames_in_flight, swapchain_images.len(), &meshes)
+            .inspect_err(|_| vk.destroy_target(surface, swapchain, &swapchain_image_views, offscreen.as_ref()))?;
 
-        unsafe { vk.device.destroy_shader_module(  vertex_module, None); }
-        unsafe { vk.device.destroy_shader_module(fragment_module, None); }
+        #[cfg(feature = "hot_reload")]
+        let shader_watcher = ShaderWatcher::new();
 
         let clear_value = vk::ClearValue { color: vk::ClearColorValue {
             float32: clear_color
@@ -954,45 +2690,16 @@ impl Renderer {
 
         let frame_index = 0;
 
-        let (vertices, _) = vk.create_buffer(
-            &meshes.vertices,
-            vk::BufferUsageFlags::VERTEX_BUFFER
-        );
-        unsafe { vk.device.unmap_memory(vertices.1); }
-
-        let (indices, _) = vk.create_buffer(
-            &meshes.indices,
-            vk::BufferUsageFlags::INDEX_BUFFER
-        );
-        unsafe { vk.device.unmap_memory(indices.1); }
-
-        let (instances, mapped_instances_dst) = vk.create_buffer(
-            &meshes.instances,
-            vk::BufferUsageFlags::VERTEX_BUFFER
-        );
-
-        let vertices_pointer = {
-            let buffer_device_address_info = vk::BufferDeviceAddressInfo::default()
-                .buffer(vertices.0);
-
-            unsafe { vk.device.get_buffer_device_address(&buffer_device_address_info) }
-        };
-
-        let indices_pointer = {
-            let buffer_device_address_info = vk::BufferDeviceAddressInfo::default()
-                .buffer(indices.0);
-
-            unsafe { vk.device.get_buffer_device_address(&buffer_device_address_info) }
-        };
+        let instances_capacity = meshes.instances.len();
+        let instances_pointer  = vk.get_buffer_device_address(instances.0);
 
-        let instances_pointer = {
-            let buffer_device_address_info = vk::BufferDeviceAddressInfo::default()
-                .buffer(instances.0);
+        let vertices_len = meshes.vertices.len();
+        let  indices_len = meshes. indices.len();
 
-            unsafe { vk.device.get_buffer_device_address(&buffer_device_address_info) }
-        };
+        let mut camera = Camera2D::default();
+        camera.set_viewport_size(viewports[0].width, viewports[0].height);
 
-        Self {
+        Ok(Self {
             surface,
             image_extent,
             swapchain,
@@ -1007,21 +2714,52 @@ impl Renderer {
             viewports,
             scissors,
             compute_pipeline_layout,
-            compute_pipeline,
+            // NOTE: created in `Vulkan::sync_meshes`, like the graphics pipelines
+            compute_passes: Vec::new(),
             graphics_pipeline_layout,
-            graphics_pipeline,
+            // NOTE: created in `Vulkan::sync_meshes`
+            pipelines: Vec::new(),
             clear_value,
             frame_index,
-            max_frames_in_flight,
+            frames_in_flight,
             vertices,
             indices,
             instances,
             meshes,
+            camera,
             vertices_pointer,
             indices_pointer,
             instances_pointer,
-            mapped_instances_dst
-        }
+            mapped_instances_dst,
+            vertices_len,
+            indices_len,
+            instances_capacity,
+            offscreen,
+            timestamps,
+            window_extent:          image_extent,
+            swapchain_outdated:     false,
+            // NOTE: set in `Renderer::new`, headless renderers do not present
+            requested_present_mode: PresentMode::default(),
+            present_mode:           PresentMode::default(),
+            color_space:            ColorSpace::Srgb,
+            surface_format:         SRGB_SURFACE_FORMAT,
+            descriptor_set_layout,
+            descriptor_pool,
+            descriptor_set,
+            sampler,
+            textures: Vec::new(),
+            #[cfg(feature = "hot_reload")]
+            shader_watcher
+        })
+    }
+
+    fn check_vertex_strides(&self) {
+        let shader_vertex_strides = self.pipelines
+            .iter()
+            .map(|&(_, shader_vertex_stride)| shader_vertex_stride)
+            .collect::<Vec<_>>();
+
+        self.meshes.check_vertex_strides(&shader_vertex_strides);
     }
 
     #[inline]
@@ -1030,83 +2768,170 @@ impl Renderer {
             self.swapchain_image_views
                 .iter()
                 .for_each(|image_view| vk.device.destroy_image_view(*image_view, None));
+        }
 
-            vk.ext_swapchain.destroy_swapchain(self.swapchain, None);
+        // NOTE: headless devices do not load the swapchain functions
+        if self.swapchain != vk::SwapchainKHR::null() {
+            unsafe { vk.ext_swapchain.destroy_swapchain(self.swapchain, None); }
+        }
+    }
+
+    fn destroy_render_finished_semaphores(&self, vk: &Vulkan) {
+        self.render_finished_semaphores
+            .iter()
+            .for_each(|semaphore| unsafe { vk.device.destroy_semaphore(*semaphore, None) });
+    }
+
+    fn destroy_offscreen(&mut self, vk: &Vulkan) {
+        if let Some(offscreen) = self.offscreen.take() {
+            vk.destroy_offscreen(&offscreen);
         }
     }
 
     fn destroy(mut self, vk: &Vulkan) {
         unsafe {
-            vk.device.unmap_memory(self.instances.1);
-            vk.device.free_memory(self.instances.1, None);
-            vk.device.free_memory(self.indices.1, None);
-            vk.device.free_memory(self.vertices.1, None);
-            vk.device.destroy_buffer(self.instances.0, None);
-            vk.device.destroy_buffer(self.indices.0, None);
-            vk.device.destroy_buffer(self.vertices.0, None);
-            vk.device.destroy_pipeline(self.compute_pipeline, None);
+            vk.destroy_buffer(self.instances);
+            vk.destroy_buffer(self.indices);
+            vk.destroy_buffer(self.vertices);
+            self.compute_passes
+                .iter()
+                .for_each(|&(compute_pipeline, _)| vk.device.destroy_pipeline(compute_pipeline, None));
             vk.device.destroy_pipeline_layout(self.compute_pipeline_layout, None);
-            vk.device.destroy_pipeline(self.graphics_pipeline, None);
+
+            self.pipelines
+                .iter()
+                .for_each(|&(pipeline, _)| vk.device.destroy_pipeline(pipeline, None));
             vk.device.destroy_pipeline_layout(self.graphics_pipeline_layout, None);
+
+            self.textures
+                .iter()
+                .for_each(|&(image, image_memory, image_view)| {
+                    vk.device.destroy_image_view(image_view, None);
+                    vk.device.destroy_image(image, None);
+                    vk.free_memory(image_memory);
+                });
+
+            vk.device.destroy_sampler(self.sampler, None);
+            vk.device.destroy_descriptor_pool(self.descriptor_pool, None);
+            vk.device.destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+
             vk.device.destroy_command_pool(self.command_pool, None);
 
+            self.timestamps.destroy(&vk.device);
+
             self.in_flight_fences
                 .iter()
                 .for_each(|fence| vk.device.destroy_fence(*fence, None));
 
-            self.render_finished_semaphores
-                .iter()
-                .for_each(|semaphore| vk.device.destroy_semaphore(*semaphore, None));
+            self.destroy_render_finished_semaphores(vk);
             self.image_ready_semaphores
                 .iter()
                 .for_each(|semaphore| vk.device.destroy_semaphore(*semaphore, None));
 
             self.destroy_swapchain_and_image_views(vk);
+            self.destroy_offscreen(vk);
 
             vk.ext_surface.destroy_surface(self.surface, None);
         }
     }
 }
 
-#[must_use]
-fn read_spirv(filepath: &str) -> Vec<u32> {
-    let bytes = fs::read(format!("{filepath}.spv"))
-        .unwrap_or_else(|err| {
-            panic!("failed to read `{filepath}`, perhaps you forgot to compile the shader: {err}");
-        });
+// NOTE: relies on the `InstanceBuffer { Instance data[]; }` naming in the shaders,
+//       shaders without debug names or without instances are not checked
+fn check_instance_stride<I: Instance>(code: &[u32], name: &str) -> Result<(), String> {
+    let Some(stride) = find_array_stride(code, "InstanceBuffer") else {
+        return Ok(());
+    };
+
+    if stride != mem::size_of::<I>() {
+        return Err(format!(
+            "`{}` is {} bytes, but the `Instance` in `{name}` is {stride} bytes",
+            any::type_name::<I>(),
+            mem::size_of::<I>()
+        ));
+    }
 
-    assert!(!bytes.is_empty(),      "invalid SPIR-V file (empty file)");
-    assert!((bytes.len() % 4) == 0, "invalid SPIR-V file (byte count is not divisible by 4)");
+    Ok(())
+}
+
+// finds the ArrayStride of the runtime array inside the block named `block_name`
+#[must_use]
+fn find_array_stride(code: &[u32], block_name: &str) -> Option<usize> {
+    const OP_NAME:                 u32 =  5;
+    const OP_TYPE_RUNTIME_ARRAY:   u32 = 29;
+    const OP_TYPE_STRUCT:          u32 = 30;
+    const OP_DECORATE:             u32 = 71;
+    const DECORATION_ARRAY_STRIDE: u32 =  6;
+
+    let mut block_id       = None;
+    let mut struct_members = HashMap::new();
+    let mut array_ids      = Vec::new();
+    let mut array_strides  = HashMap::new();
+
+    // NOTE: skips the 5 word header
+    let mut instructions = code.get(5..)?;
+
+    while let Some(first_word) = instructions.first() {
+        let word_count = (first_word >> 16_u32) as usize;
+        let opcode     = first_word & 0xffff;
+        let operands   = instructions.get(1..word_count.max(1))?;
+
+        match (opcode, operands) {
+            (OP_NAME, &[id, ref name @ ..]) => {
+                let bytes = name
+                    .iter()
+                    .flat_map(|word| word.to_le_bytes())
+                    .take_while(|byte| *byte != 0)
+                    .collect::<Vec<_>>();
+
+                if bytes == block_name.as_bytes() {
+                    block_id = Some(id);
+                }
+            },
+            (OP_TYPE_STRUCT, &[id, first_member, ..]) => {
+                struct_members.insert(id, first_member);
+            },
+            (OP_TYPE_RUNTIME_ARRAY, &[id, ..]) => {
+                array_ids.push(id);
+            },
+            (OP_DECORATE, &[id, DECORATION_ARRAY_STRIDE, stride]) => {
+                array_strides.insert(id, stride as usize);
+            },
+            _ => ()
+        }
 
-    let mut words = Vec::with_capacity(bytes.len() / 4);
-    for chunk in bytes.chunks(4) {
-        let mut word = [0_u8; 4];
-        word.copy_from_slice(chunk);
-        words.push(u32::from_ne_bytes(word));
+        instructions = instructions.get(word_count.max(1)..)?;
     }
 
-    assert!(words[0] == 0x0723_0203, "invalid SPIR-V file (first word is not SPIR-V magic number)");
+    let array_id = struct_members.get(&block_id?)?;
+
+    if !array_ids.contains(array_id) {
+        return None;
+    }
 
-    words
+    array_strides.get(array_id).copied()
 }
 
+// finds the LocalSize execution mode, which GLSL emits for `layout(local_size_x = ...) in;`
 #[must_use]
-fn find_memory_type_index(
-    memory_properties:   &vk::PhysicalDeviceMemoryProperties,
-    memory_type_bits:    u32,
-    required_properties: vk::MemoryPropertyFlags
-) -> Option<u32> {
-    for i in 0..memory_properties.memory_type_count {
-        let contains = memory_properties
-            .memory_types[i as usize]
-            .property_flags
-            .contains(required_properties);
-
-        if contains && (memory_type_bits & (1 << i)) != 0 {
-            return Some(i);
+fn find_local_size(code: &[u32]) -> Option<[u32; 3]> {
+    const OP_EXECUTION_MODE:         u32 = 16;
+    const EXECUTION_MODE_LOCAL_SIZE: u32 = 17;
+
+    // NOTE: skips the 5 word header
+    let mut instructions = code.get(5..)?;
+
+    while let Some(first_word) = instructions.first() {
+        let word_count = (first_word >> 16_u32) as usize;
+        let opcode     = first_word & 0xffff;
+        let operands   = instructions.get(1..word_count.max(1))?;
+
+        if let (OP_EXECUTION_MODE, &[_, EXECUTION_MODE_LOCAL_SIZE, x, y, z]) = (opcode, operands) {
+            return Some([x, y, z]);
         }
+
+        instructions = instructions.get(word_count.max(1)..)?;
     }
 
     None
 }
-
@@ -1,27 +1,50 @@
 // dacho/crates/dacho_renderer/src/mesh.rs
 
-pub type   Vertex = [f32;   VERTEX_SIZE];
-pub type    Index = [u32;    INDEX_SIZE];
-pub type Instance = [f32; INSTANCE_SIZE];
+pub type Index = [u32; INDEX_SIZE];
 
-pub const   VERTEX_SIZE: usize = 2;
-pub const    INDEX_SIZE: usize = 3;
-pub const INSTANCE_SIZE: usize = 2;
+pub const INDEX_SIZE: usize = 3;
+
+/// Per-vertex data, read by the shaders as the `Vertex` struct inside `VertexBuffer`.
+///
+/// # Safety
+///
+/// The type has to be `#[repr(C)]` plain old data without any padding,
+/// because it is copied to GPU memory as bytes.
+/// Its size is checked against the stride of `VertexBuffer` in the shaders.
+pub unsafe trait Vertex: Copy + 'static {}
+
+// SAFETY: plain old data without padding
+unsafe impl Vertex for [f32; 2] {}
+
+/// Per-instance data, read by the shaders as the `Instance` struct inside `InstanceBuffer`.
+///
+/// # Safety
+///
+/// The type has to be `#[repr(C)]` plain old data, that is valid for any bit pattern,
+/// because it is copied to and from GPU memory as bytes.
+/// Its size is checked against the stride of `InstanceBuffer` in the shaders.
+pub unsafe trait Instance: Copy + Default + 'static {}
+
+// SAFETY: plain old data
+unsafe impl Instance for [f32; 2] {}
 
 pub trait Mesh {
-    fn vertices() -> impl IntoIterator<Item = Vertex>;
-    fn  indices() -> impl IntoIterator<Item =  Index>;
+    type Vertex: Vertex;
+
+    fn vertices() -> impl IntoIterator<Item = Self::Vertex>;
+    fn  indices() -> impl IntoIterator<Item =       Index>;
 }
 
 pub struct Quad;
 impl Mesh for Quad {
-    fn vertices() -> impl IntoIterator<Item = Vertex> {
+    type Vertex = [f32; 2];
+
+    fn vertices() -> impl IntoIterator<Item = Self::Vertex> {
         [
-            // TODO: temp not unit sized
-            [-0.05, -0.05],
-            [-0.05,  0.05],
-            [ 0.05, -0.05],
-            [ 0.05,  0.05]
+            [-0.5, -0.5],
+            [-0.5,  0.5],
+            [ 0.5, -0.5],
+            [ 0.5,  0.5]
         ]
     }
 
@@ -35,7 +58,9 @@ impl Mesh for Quad {
 
 pub struct Circle;
 impl Mesh for Circle {
-    fn vertices() -> impl IntoIterator<Item = Vertex> {
+    type Vertex = [f32; 2];
+
+    fn vertices() -> impl IntoIterator<Item = Self::Vertex> {
         [
             [ 0.0, -0.50],
             [ 0.0,  0.00],
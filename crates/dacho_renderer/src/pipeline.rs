@@ -0,0 +1,198 @@
+// dacho/crates/dacho_renderer/src/pipeline.rs
+
+use std::fs;
+use std::path::PathBuf;
+#[cfg(feature = "hot_reload")]
+use std::path::Path;
+
+use ash::vk;
+
+#[cfg(feature = "glsl")]
+use crate::glsl;
+
+
+#[derive(Clone)]
+pub enum ShaderSource {
+    // SPIR-V, for example from `include_bytes!`
+    Bytes(Vec<u8>),
+    // path to a SPIR-V file, relative to the working directory
+    Path(PathBuf),
+    // path to a GLSL file, compiled when the pipeline is created
+    #[cfg(feature = "glsl")]
+    Glsl(PathBuf)
+}
+
+// embeds a shader compiled by `glsl::compile_shaders` in a build script,
+// `$path` is relative to the directory that was compiled
+#[cfg(not(feature = "hot_reload"))]
+#[macro_export]
+macro_rules! include_shader {
+    ($path:literal) => {
+        $crate::pipeline::ShaderSource::bytes(
+            include_bytes!(concat!(env!("OUT_DIR"), "/shaders/", $path, ".spv")).as_slice()
+        )
+    };
+}
+
+// NOTE: debug builds compile the GLSL source instead, so it can be watched for changes
+#[cfg(feature = "hot_reload")]
+#[macro_export]
+macro_rules! include_shader {
+    ($path:literal) => {
+        if cfg!(debug_assertions) {
+            $crate::pipeline::ShaderSource::glsl(concat!(env!("DACHO_SHADERS_DIR"), "/", $path))
+        } else {
+            $crate::pipeline::ShaderSource::bytes(
+                include_bytes!(concat!(env!("OUT_DIR"), "/shaders/", $path, ".spv")).as_slice()
+            )
+        }
+    };
+}
+
+impl ShaderSource {
+    #[must_use]
+    pub fn bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        Self::Bytes(bytes.into())
+    }
+
+    #[must_use]
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self::Path(path.into())
+    }
+
+    #[cfg(feature = "glsl")]
+    #[must_use]
+    pub fn glsl(path: impl Into<PathBuf>) -> Self {
+        Self::Glsl(path.into())
+    }
+
+    // NOTE: Err is a message for the user
+    pub(crate) fn read(&self) -> Result<Vec<u32>, String> {
+        match *self {
+            Self::Bytes(ref bytes) => spirv_words(bytes),
+            Self::Path (ref path)  => {
+                let bytes = fs::read(path)
+                    .map_err(|err| format!("failed to read `{}`, perhaps you forgot to compile the shader: {err}", path.display()))?;
+
+                spirv_words(&bytes)
+            },
+            #[cfg(feature = "glsl")]
+            Self::Glsl (ref path)  => glsl::compile(path)
+        }
+    }
+
+    // the file to watch for changes, if there is one
+    #[cfg(feature = "hot_reload")]
+    #[must_use]
+    pub(crate) fn file(&self) -> Option<&Path> {
+        match *self {
+            Self::Bytes(_)                                => None,
+            Self::Path (ref path) | Self::Glsl(ref path) => Some(path)
+        }
+    }
+
+    // for error messages
+    #[must_use]
+    pub(crate) fn name(&self) -> String {
+        match *self {
+            Self::Bytes(_)        => String::from("<bytes>"),
+            Self::Path (ref path) => path.display().to_string(),
+            #[cfg(feature = "glsl")]
+            Self::Glsl (ref path) => path.display().to_string()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub enum Blend {
+    #[default]
+    Opaque,
+    // straight (not premultiplied) alpha
+    Alpha,
+    Additive
+}
+
+impl Blend {
+    #[inline]
+    pub(crate) fn attachment_state(self) -> vk::PipelineColorBlendAttachmentState {
+        let state = vk::PipelineColorBlendAttachmentState::default()
+            .color_write_mask(vk::ColorComponentFlags::RGBA);
+
+        let (dst_color_blend_factor, dst_alpha_blend_factor) = match self {
+            Self::Opaque   => return state.blend_enable(false),
+            Self::Alpha    => (vk::BlendFactor::ONE_MINUS_SRC_ALPHA, vk::BlendFactor::ONE_MINUS_SRC_ALPHA),
+            Self::Additive => (vk::BlendFactor::ONE,                 vk::BlendFactor::ONE)
+        };
+
+        state
+            .blend_enable(true)
+            .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
+            .dst_color_blend_factor(dst_color_blend_factor)
+            .color_blend_op(vk::BlendOp::ADD)
+            .src_alpha_blend_factor(vk::BlendFactor::ONE)
+            .dst_alpha_blend_factor(dst_alpha_blend_factor)
+            .alpha_blend_op(vk::BlendOp::ADD)
+    }
+}
+
+// what the `view_projection` push constant maps to clip space
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Space {
+    // world units, through `Renderer::camera`
+    #[default]
+    World,
+    // pixels from the top left corner of the window, the camera is ignored,
+    // for things like a HUD
+    Screen
+}
+
+#[derive(Clone)]
+pub struct PipelineDescription {
+    pub vertex:    ShaderSource,
+    pub fragment:  ShaderSource,
+    pub topology:  vk::PrimitiveTopology,
+    pub cull_mode: vk::CullModeFlags,
+    pub blend:     Blend,
+    pub space:     Space
+}
+
+impl PipelineDescription {
+    // NOTE: the rest of the fields can be changed with `..PipelineDescription::new(vertex, fragment)`
+    #[must_use]
+    pub const fn new(vertex: ShaderSource, fragment: ShaderSource) -> Self {
+        Self {
+            vertex,
+            fragment,
+            topology:  vk::PrimitiveTopology::TRIANGLE_LIST,
+            cull_mode: vk::CullModeFlags::BACK,
+            blend:     Blend::Opaque,
+            space:     Space::World
+        }
+    }
+}
+
+// index into the pipelines added with `Meshes::add_pipeline`
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pipeline(pub(crate) usize);
+
+fn spirv_words(bytes: &[u8]) -> Result<Vec<u32>, String> {
+    if bytes.is_empty() {
+        return Err(String::from("invalid SPIR-V file (empty file)"));
+    }
+    if !bytes.len().is_multiple_of(4) {
+        return Err(String::from("invalid SPIR-V file (byte count is not divisible by 4)"));
+    }
+
+    let mut words = Vec::with_capacity(bytes.len() / 4);
+    for chunk in bytes.chunks(4) {
+        let mut word = [0_u8; 4];
+        word.copy_from_slice(chunk);
+        words.push(u32::from_ne_bytes(word));
+    }
+
+    if words[0] != 0x0723_0203 {
+        return Err(String::from("invalid SPIR-V file (first word is not SPIR-V magic number)"));
+    }
+
+    Ok(words)
+}
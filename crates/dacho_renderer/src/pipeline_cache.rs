@@ -0,0 +1,154 @@
+// dacho/crates/dacho_renderer/src/pipeline_cache.rs
+
+use std::{env, fs, path::{Path, PathBuf}};
+
+use ash::vk;
+
+
+// set to a file path to load and save the pipeline cache there,
+// takes precedence over `VulkanConfig::pipeline_cache`
+pub const PIPELINE_CACHE_ENV_VAR: &str = "DACHO_PIPELINE_CACHE";
+
+// bumped whenever the file layout changes
+const MAGIC: &[u8] = b"dacho pipeline cache 1\0";
+
+// loaded from `path` if it matches the device, and saved back to it on shutdown
+pub(crate) struct PipelineCache {
+    pub(crate) handle: vk::PipelineCache,
+               path:   Option<PathBuf>,
+               header: Vec<u8>
+}
+
+impl PipelineCache {
+    // NOTE: a null handle if it can not be created, pipelines are then just created without a cache
+    #[must_use]
+    pub(crate) fn new(device: &ash::Device, properties: &vk::PhysicalDeviceProperties, configured: Option<&Path>) -> Self {
+        let path = env::var_os(PIPELINE_CACHE_ENV_VAR)
+            .map(PathBuf::from)
+            .or_else(|| configured.map(Path::to_path_buf));
+
+        let header = header(properties);
+
+        let data = path
+            .as_deref()
+            .and_then(|file| fs::read(file).ok())
+            .unwrap_or_default();
+
+        let pipeline_cache_create_info = vk::PipelineCacheCreateInfo::default()
+            .initial_data(initial_data(&data, &header));
+        let handle = unsafe { device.create_pipeline_cache(&pipeline_cache_create_info, None) }
+            .unwrap_or_default();
+
+        Self { handle, path, header }
+    }
+
+    // NOTE: a failed save only costs time at the next startup
+    pub(crate) fn save_and_destroy(&self, device: &ash::Device) {
+        if let Some(path) = self.path.as_ref()
+            && self.handle != vk::PipelineCache::null()
+        {
+            let saved = unsafe { device.get_pipeline_cache_data(self.handle) }
+                .map_err(|err| err.to_string())
+                .and_then(|data| {
+                    // NOTE: written next to it first, so a crash midway does not leave a truncated file
+                    let temporary = path.with_extension("tmp");
+
+                    fs::write(&temporary, [self.header.as_slice(), &data].concat())
+                        .and_then(|()| fs::rename(&temporary, path))
+                        .map_err(|err| err.to_string())
+                });
+
+            if let Err(err) = saved {
+                log::warn!(target: "dacho", "failed to save the pipeline cache to `{}`: {err}", path.display());
+            }
+        }
+
+        unsafe { device.destroy_pipeline_cache(self.handle, None); }
+    }
+}
+
+// what the saved data has to start with to be used
+#[must_use]
+fn header(properties: &vk::PhysicalDeviceProperties) -> Vec<u8> {
+    [
+        MAGIC,
+        &properties.vendor_id     .to_le_bytes(),
+        &properties.device_id     .to_le_bytes(),
+        &properties.driver_version.to_le_bytes(),
+        &properties.pipeline_cache_uuid
+    ].concat()
+}
+
+// the saved data without the header, or nothing if it is not for this device, driver or file layout
+//
+// NOTE: drivers do not all check it as carefully as they should
+#[must_use]
+fn initial_data<'data>(data: &'data [u8], header: &[u8]) -> &'data [u8] {
+    data
+        .strip_prefix(header)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    fn properties() -> vk::PhysicalDeviceProperties {
+        vk::PhysicalDeviceProperties {
+            vendor_id:           0x10de,
+            device_id:           0x2684,
+            driver_version:      0x0230_0000,
+            pipeline_cache_uuid: [7; vk::UUID_SIZE],
+            ..Default::default()
+        }
+    }
+
+    fn saved(properties: &vk::PhysicalDeviceProperties, cache: &[u8]) -> Vec<u8> {
+        [header(properties).as_slice(), cache].concat()
+    }
+
+    #[test]
+    fn matching_header_is_stripped() {
+        let data = saved(&properties(), b"cache");
+
+        assert_eq!(initial_data(&data, &header(&properties())), b"cache");
+    }
+
+    #[test]
+    fn other_device_is_discarded() {
+        let others = [
+            vk::PhysicalDeviceProperties { vendor_id:           0x1002,             ..properties() },
+            vk::PhysicalDeviceProperties { device_id:           0x2685,             ..properties() },
+            vk::PhysicalDeviceProperties { driver_version:      0x0230_0001,        ..properties() },
+            vk::PhysicalDeviceProperties { pipeline_cache_uuid: [8; vk::UUID_SIZE], ..properties() }
+        ];
+
+        for (i, other) in others.iter().enumerate() {
+            let data = saved(other, b"cache");
+
+            assert!(initial_data(&data, &header(&properties())).is_empty(), "{i}");
+        }
+    }
+
+    #[test]
+    fn other_file_layout_is_discarded() {
+        let header = header(&properties());
+        let data   = [b"dacho pipeline cache 0\0", &header[MAGIC.len()..], b"cache"].concat();
+
+        assert!(initial_data(&data, &header).is_empty());
+    }
+
+    #[test]
+    fn truncated_file_is_discarded() {
+        let header = header(&properties());
+        let data   = saved(&properties(), b"cache");
+
+        for len in [0, 1, MAGIC.len(), header.len() - 1] {
+            assert!(initial_data(&data[..len], &header).is_empty(), "{len}");
+        }
+
+        // NOTE: an empty cache is still valid, the driver just starts from nothing
+        assert!(initial_data(&data[..header.len()], &header).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+// dacho/crates/dacho_renderer/src/profiler.rs
+
+use std::{iter, time::Duration};
+
+use ash::vk;
+
+use crate::Error;
+
+
+// a begin and an end timestamp per scope, `PostRenderBarrier` is the last one
+const QUERY_COUNT: u32 = GpuScope::PostRenderBarrier.begin_query() + 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GpuScope {
+    // every compute pass, with the barriers between them
+    Compute,
+    // the image layout transition into a color attachment
+    PreRenderBarrier,
+    // the dynamic rendering, so every draw
+    Rendering,
+    // the image layout transition out of a color attachment, for presenting or reading back
+    PostRenderBarrier
+}
+
+impl GpuScope {
+    // NOTE: in the order of their queries
+    const ALL: [Self; 4] = [Self::Compute, Self::PreRenderBarrier, Self::Rendering, Self::PostRenderBarrier];
+
+    #[must_use]
+    const fn begin_query(self) -> u32 {
+        2 * self as u32
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GpuTiming {
+    pub scope:    GpuScope,
+    pub duration: Duration
+}
+
+// one query pool per frame in flight, empty if the queue can not write timestamps
+pub(crate) struct Timestamps {
+    query_pools: Vec<vk::QueryPool>,
+    // nanoseconds per tick
+    period:      f64,
+    timings:     Vec<GpuTiming>
+}
+
+impl Timestamps {
+    pub(crate) fn new(device: &ash::Device, frames_in_flight: u32, timestamp_period: Option<f32>) -> Result<Self, Error> {
+        let Some(period) = timestamp_period else {
+            return Ok(Self { query_pools: Vec::new(), period: 0.0, timings: Vec::new() });
+        };
+
+        let query_pool_create_info = vk::QueryPoolCreateInfo::default()
+            .query_type(vk::QueryType::TIMESTAMP)
+            .query_count(QUERY_COUNT);
+        let query_pools = iter::repeat_with(|| {
+            unsafe { device.create_query_pool(&query_pool_create_info, None) }
+        }).take(frames_in_flight as usize).collect::<Result<_, _>>().map_err(Error::Device)?;
+
+        Ok(Self { query_pools, period: f64::from(period), timings: Vec::new() })
+    }
+
+    // NOTE: before the first scope of every frame
+    pub(crate) fn reset(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, frame_index: u32) {
+        if let Some(&query_pool) = self.query_pools.get(frame_index as usize) {
+            unsafe { device.cmd_reset_query_pool(command_buffer, query_pool, 0, QUERY_COUNT); }
+        }
+    }
+
+    // NOTE: once after creating them, queries can not be read before they were reset
+    pub(crate) fn reset_all(&self, device: &ash::Device, command_buffer: vk::CommandBuffer) {
+        for &query_pool in &self.query_pools {
+            unsafe { device.cmd_reset_query_pool(command_buffer, query_pool, 0, QUERY_COUNT); }
+        }
+    }
+
+    pub(crate) fn begin(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, frame_index: u32, scope: GpuScope) {
+        self.write(device, command_buffer, frame_index, scope.begin_query());
+    }
+
+    pub(crate) fn end(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, frame_index: u32, scope: GpuScope) {
+        self.write(device, command_buffer, frame_index, scope.begin_query() + 1);
+    }
+
+    // NOTE: after everything recorded before it finished
+    fn write(&self, device: &ash::Device, command_buffer: vk::CommandBuffer, frame_index: u32, query: u32) {
+        if let Some(&query_pool) = self.query_pools.get(frame_index as usize) {
+            unsafe { device.cmd_write_timestamp2(command_buffer, vk::PipelineStageFlags2::ALL_COMMANDS, query_pool, query); }
+        }
+    }
+
+    // NOTE: the frame has to be finished, keeps the previous timings if it was not recorded yet
+    pub(crate) fn resolve(&mut self, device: &ash::Device, frame_index: u32) -> Result<(), Error> {
+        let Some(&query_pool) = self.query_pools.get(frame_index as usize) else {
+            return Ok(());
+        };
+
+        let mut ticks = [0_u64; QUERY_COUNT as usize];
+
+        match unsafe { device.get_query_pool_results(query_pool, 0, &mut ticks, vk::QueryResultFlags::TYPE_64) } {
+            Ok(())                     => {},
+            Err(vk::Result::NOT_READY) => return Ok(()),
+            Err(err)                   => return Err(Error::Device(err))
+        }
+
+        self.timings = GpuScope::ALL
+            .iter()
+            .zip(ticks.chunks_exact(2))
+            .map(|(&scope, begin_and_end)| {
+                let elapsed = begin_and_end[1].saturating_sub(begin_and_end[0]) as f64 * self.period;
+
+                GpuTiming { scope, duration: Duration::from_secs_f64(elapsed / 1e9) }
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    #[must_use]
+    pub(crate) fn timings(&self) -> &[GpuTiming] {
+        &self.timings
+    }
+
+    pub(crate) fn destroy(&self, device: &ash::Device) {
+        for &query_pool in &self.query_pools {
+            unsafe { device.destroy_query_pool(query_pool, None); }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+// dacho/crates/dacho_renderer/src/snapshot.rs
+
+use std::{env, fs, io};
+use std::path::{Path, PathBuf};
+
+use crate::{mesh::Instance, texture::read_png, Error, Meshes, Vulkan, VulkanConfig};
+
+
+// set to anything to record missing snapshots, and overwrite the stored ones with the current output
+const UPDATE_ENV_VAR: &str = "DACHO_UPDATE_SNAPSHOTS";
+
+pub struct SnapshotConfig {
+    pub width:       u32,
+    pub height:      u32,
+    pub frames:      usize,
+    pub tolerance:   u8,
+    pub clear_color: [f32; 4],
+    pub directory:   PathBuf
+}
+
+impl Default for SnapshotConfig {
+    // NOTE: `CARGO_MANIFEST_DIR` is set by `cargo test` to the crate being tested
+    fn default() -> Self {
+        let directory = env::var_os("CARGO_MANIFEST_DIR")
+            .map_or_else(PathBuf::new, PathBuf::from)
+            .join("tests")
+            .join("snapshots");
+
+        Self {
+            width:       256,
+            height:      256,
+            frames:      1,
+            tolerance:   2,
+            clear_color: [0.0, 0.0, 0.0, 1.0],
+            directory
+        }
+    }
+}
+
+pub struct Comparison {
+    pub mismatched_pixels: usize,
+    pub max_difference:    u8,
+    pub diff:              Vec<u8>
+}
+
+// renders `meshes` headlessly for `config.frames` frames, and compares the last one
+// against `{config.directory}/{name}.png` with a per-channel tolerance
+//
+// a missing snapshot fails, unless `DACHO_UPDATE_SNAPSHOTS` is set to record it,
+// on a mismatch the output and a diff image are written next to it as `{name}.actual.png` and `{name}.diff.png`
+//
+// skipped when there is no vulkan driver or no suitable device, any other error fails
+#[expect(clippy::print_stderr, reason = "the test harness shows skipped snapshots on stderr")]
+pub fn assert_snapshot<I: Instance>(name: &str, meshes: Meshes<I>, config: &SnapshotConfig) {
+    assert!(config.frames != 0, "a snapshot needs at least one frame");
+
+    let Some(actual) = render(meshes, config) else {
+        eprintln!("skipping snapshot `{name}`, no vulkan driver or device");
+        return;
+    };
+
+    fs::create_dir_all(&config.directory)
+        .unwrap();
+
+    let expected_path = config.directory.join(format!("{name}.png"));
+    let   actual_path = config.directory.join(format!("{name}.actual.png"));
+    let     diff_path = config.directory.join(format!("{name}.diff.png"));
+
+    if env::var_os(UPDATE_ENV_VAR).is_some() {
+        write_png(&expected_path, config.width, config.height, &actual);
+        return;
+    }
+
+    let stored = read_png(&expected_path);
+
+    if stored.is_none() {
+        write_png(&actual_path, config.width, config.height, &actual);
+    }
+
+    assert!(
+        stored.is_some(),
+        "snapshot `{name}` is missing or not a PNG, the output was written to `{}` (set `{UPDATE_ENV_VAR}` to record it)",
+        actual_path.display()
+    );
+
+    let Some((expected_width, expected_height, expected)) = stored else {
+        return;
+    };
+
+    assert!(
+        (expected_width, expected_height) == (config.width, config.height),
+        "snapshot `{name}` is {expected_width}x{expected_height}, but was rendered at {}x{} (set `{UPDATE_ENV_VAR}` to overwrite it)",
+        config.width, config.height
+    );
+
+    let comparison = compare(&expected, &actual, config.tolerance);
+
+    if comparison.mismatched_pixels == 0 {
+        // NOTE: clean up after a previous failure
+        remove_if_exists(&actual_path);
+        remove_if_exists(&diff_path);
+
+        return;
+    }
+
+    write_png(&actual_path, config.width, config.height, &actual);
+    write_png(  &diff_path, config.width, config.height, &comparison.diff);
+
+    assert!(
+        comparison.mismatched_pixels == 0,
+        "snapshot `{name}` has {} mismatched pixels (max channel difference {}, tolerance {}), see `{}`",
+        comparison.mismatched_pixels,
+        comparison.max_difference,
+        config.tolerance,
+        diff_path.display()
+    );
+}
+
+// compares two RGBA8 images of the same size,
+// the diff has mismatched pixels in red and the rest as a faded `expected`
+#[must_use]
+pub fn compare(expected: &[u8], actual: &[u8], tolerance: u8) -> Comparison {
+    assert!(expected.len() == actual.len(), "compared images have different sizes");
+
+    let mut mismatched_pixels = 0;
+    let mut max_difference    = 0;
+    let mut diff              = Vec::with_capacity(expected.len());
+
+    for (expected_pixel, actual_pixel) in expected.chunks_exact(4).zip(actual.chunks_exact(4)) {
+        let difference = expected_pixel
+            .iter()
+            .zip(actual_pixel)
+            .map(|(expected_channel, actual_channel)| expected_channel.abs_diff(*actual_channel))
+            .max()
+            .unwrap_or(0);
+
+        max_difference = max_difference.max(difference);
+
+        if difference > tolerance {
+            mismatched_pixels += 1;
+            diff.extend([255, 0, 0, 255]);
+        } else {
+            let luminance = expected_pixel[..3].iter().map(|channel| u16::from(*channel)).sum::<u16>() / 3;
+            let faded     = u8::try_from(luminance / 4).unwrap();
+
+            diff.extend([faded, faded, faded, 255]);
+        }
+    }
+
+    Comparison { mismatched_pixels, max_difference, diff }
+}
+
+#[must_use]
+fn render<I: Instance>(meshes: Meshes<I>, config: &SnapshotConfig) -> Option<Vec<u8>> {
+    // NOTE: validation errors fail the snapshot, if the layer is installed
+    #[cfg_attr(not(feature = "validation"), expect(unused_mut, reason = "only changed with validation"))]
+    let mut vulkan_config = VulkanConfig::default();
+
+    #[cfg(feature = "validation")]
+    {
+        vulkan_config.validation                = true;
+        vulkan_config.panic_on_validation_error = true;
+    }
+
+    let vulkan = match Vulkan::new_headless(&vulkan_config) {
+        Err(Error::Loader(_) | Error::NoDevice(_)) => return None,
+        result                                     => result.unwrap()
+    };
+    let mut renderer = vulkan.new_headless_renderer(config.width, config.height, config.clear_color, meshes).unwrap();
+
+    let mut pixels = Vec::new();
+    for _ in 0..config.frames {
+        pixels = vulkan.render_headless(&mut renderer).unwrap();
+    }
+
+    vulkan.device_wait_idle().unwrap();
+    vulkan.destroy_renderer(renderer);
+
+    Some(pixels)
+}
+
+fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) {
+    let file        = fs::File::create(path).unwrap();
+    let mut encoder = png::Encoder::new(io::BufWriter::new(file), width, height);
+
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    encoder
+        .write_header()
+        .unwrap()
+        .write_image_data(pixels)
+        .unwrap();
+}
+
+fn remove_if_exists(path: &Path) {
+    if let Err(err) = fs::remove_file(path) {
+        assert!(err.kind() == io::ErrorKind::NotFound, "failed to remove `{}`: {err}", path.display());
+    }
+}
@@ -0,0 +1,239 @@
+// dacho/crates/dacho_renderer/src/swapchain.rs
+
+use ash::vk;
+
+
+// what headless renderers render to
+pub(crate) const SRGB_SURFACE_FORMAT: vk::SurfaceFormatKHR = vk::SurfaceFormatKHR {
+    format:      vk::Format::R8G8B8A8_SRGB,
+    color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PresentMode {
+    // waits for the vertical blank, supported everywhere
+    #[default]
+    Vsync,
+    // waits for the vertical blank without blocking, newer frames replace the queued one,
+    // falls back to `Vsync`
+    Mailbox,
+    // does not wait, can tear, falls back to `Mailbox` and then `Vsync`
+    Immediate,
+    // waits for the vertical blank, unless a frame is late, which can then tear,
+    // falls back to `Vsync`
+    FifoRelaxed
+}
+
+impl PresentMode {
+    // the first one in the fallback chain that is in `supported`
+    #[must_use]
+    pub(crate) fn choose(self, supported: &[vk::PresentModeKHR]) -> Self {
+        let fallbacks: &[Self] = match self {
+            Self::Vsync       => &[],
+            Self::Mailbox     => &[Self::Mailbox],
+            Self::Immediate   => &[Self::Immediate, Self::Mailbox],
+            Self::FifoRelaxed => &[Self::FifoRelaxed]
+        };
+
+        // NOTE: FIFO support is required by the spec
+        fallbacks
+            .iter()
+            .copied()
+            .find(|present_mode| supported.contains(&present_mode.to_vk()))
+            .unwrap_or(Self::Vsync)
+    }
+
+    #[must_use]
+    pub(crate) const fn to_vk(self) -> vk::PresentModeKHR {
+        match self {
+            Self::Vsync       => vk::PresentModeKHR::FIFO,
+            Self::Mailbox     => vk::PresentModeKHR::MAILBOX,
+            Self::Immediate   => vk::PresentModeKHR::IMMEDIATE,
+            Self::FifoRelaxed => vk::PresentModeKHR::FIFO_RELAXED
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    // 8 bits per channel, encoded by the hardware
+    #[default]
+    Srgb,
+    // 8 bits per channel, not encoded by the hardware, so the shaders (and the clear color)
+    // have to be sRGB encoded, for surfaces without `Srgb`, falls back to `Srgb`
+    SrgbUnorm,
+    // 10 bits per channel, the shaders (and the clear color) have to be PQ encoded rec. 2020,
+    // falls back to `Srgb`
+    Hdr10,
+    // 16 bit floats, linear with sRGB primaries, where 1.0 is 80 nits and anything beyond is brighter,
+    // falls back to `Srgb`
+    ScRgb
+}
+
+impl ColorSpace {
+    // the first format of the fallback chain that is in `supported`,
+    // None if the surface supports none of the known formats
+    #[must_use]
+    pub(crate) fn choose(self, supported: &[vk::SurfaceFormatKHR]) -> Option<(Self, vk::SurfaceFormatKHR)> {
+        let fallbacks: &[Self] = match self {
+            Self::Srgb      => &[Self::Srgb,                 Self::SrgbUnorm],
+            Self::SrgbUnorm => &[Self::SrgbUnorm,            Self::Srgb],
+            Self::Hdr10     => &[Self::Hdr10,     Self::Srgb, Self::SrgbUnorm],
+            Self::ScRgb     => &[Self::ScRgb,     Self::Srgb, Self::SrgbUnorm]
+        };
+
+        fallbacks
+            .iter()
+            .find_map(|&color_space| {
+                color_space
+                    .formats()
+                    .iter()
+                    .find_map(|&format| {
+                        supported
+                            .iter()
+                            .find(|surface_format| surface_format.format == format && surface_format.color_space == color_space.to_vk())
+                    })
+                    .map(|&surface_format| (color_space, surface_format))
+            })
+    }
+
+    // in order of preference
+    #[must_use]
+    const fn formats(self) -> &'static [vk::Format] {
+        match self {
+            Self::Srgb      => &[vk::Format::R8G8B8A8_SRGB,  vk::Format::B8G8R8A8_SRGB,  vk::Format::A8B8G8R8_SRGB_PACK32],
+            Self::SrgbUnorm => &[vk::Format::R8G8B8A8_UNORM, vk::Format::B8G8R8A8_UNORM, vk::Format::A8B8G8R8_UNORM_PACK32],
+            Self::Hdr10     => &[vk::Format::A2B10G10R10_UNORM_PACK32, vk::Format::A2R10G10B10_UNORM_PACK32],
+            Self::ScRgb     => &[vk::Format::R16G16B16A16_SFLOAT]
+        }
+    }
+
+    #[must_use]
+    const fn to_vk(self) -> vk::ColorSpaceKHR {
+        match self {
+            Self::Srgb
+            | Self::SrgbUnorm => vk::ColorSpaceKHR::SRGB_NONLINEAR,
+            Self::Hdr10       => vk::ColorSpaceKHR::HDR10_ST2084_EXT,
+            Self::ScRgb       => vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    const SRGB_NONLINEAR: vk::ColorSpaceKHR = vk::ColorSpaceKHR::SRGB_NONLINEAR;
+
+    // NOTE: raw formats, ash only implements `Debug` with its `debug` feature
+    fn choose(requested: ColorSpace, supported: &[(vk::Format, vk::ColorSpaceKHR)]) -> Option<(ColorSpace, i32)> {
+        let surface_formats = supported
+            .iter()
+            .map(|&(format, color_space)| vk::SurfaceFormatKHR { format, color_space })
+            .collect::<Vec<_>>();
+
+        requested
+            .choose(&surface_formats)
+            .map(|(color_space, surface_format)| {
+                assert!(surface_format.color_space == color_space.to_vk());
+
+                (color_space, surface_format.format.as_raw())
+            })
+    }
+
+    const fn chosen(color_space: ColorSpace, format: vk::Format) -> (ColorSpace, i32) {
+        (color_space, format.as_raw())
+    }
+
+    #[test]
+    fn srgb_on_bgra_only_surface() {
+        let supported = [(vk::Format::B8G8R8A8_SRGB, SRGB_NONLINEAR), (vk::Format::B8G8R8A8_UNORM, SRGB_NONLINEAR)];
+
+        assert_eq!(choose(ColorSpace::Srgb,      &supported), Some(chosen(ColorSpace::Srgb,      vk::Format::B8G8R8A8_SRGB)));
+        assert_eq!(choose(ColorSpace::SrgbUnorm, &supported), Some(chosen(ColorSpace::SrgbUnorm, vk::Format::B8G8R8A8_UNORM)));
+    }
+
+    #[test]
+    fn srgb_prefers_rgba() {
+        let supported = [(vk::Format::B8G8R8A8_SRGB, SRGB_NONLINEAR), (vk::Format::R8G8B8A8_SRGB, SRGB_NONLINEAR)];
+
+        assert_eq!(choose(ColorSpace::Srgb, &supported), Some(chosen(ColorSpace::Srgb, vk::Format::R8G8B8A8_SRGB)));
+    }
+
+    #[test]
+    fn srgb_falls_back_to_unorm() {
+        let supported = [(vk::Format::B8G8R8A8_UNORM, SRGB_NONLINEAR)];
+
+        assert_eq!(choose(ColorSpace::Srgb, &supported), Some(chosen(ColorSpace::SrgbUnorm, vk::Format::B8G8R8A8_UNORM)));
+    }
+
+    #[test]
+    fn hdr_falls_back_to_srgb() {
+        let supported = [(vk::Format::B8G8R8A8_UNORM, SRGB_NONLINEAR), (vk::Format::B8G8R8A8_SRGB, SRGB_NONLINEAR)];
+
+        assert_eq!(choose(ColorSpace::Hdr10, &supported), Some(chosen(ColorSpace::Srgb, vk::Format::B8G8R8A8_SRGB)));
+        assert_eq!(choose(ColorSpace::ScRgb, &supported), Some(chosen(ColorSpace::Srgb, vk::Format::B8G8R8A8_SRGB)));
+
+        // NOTE: only when there is no sRGB format either
+        assert_eq!(choose(ColorSpace::Hdr10, &supported[..1]), Some(chosen(ColorSpace::SrgbUnorm, vk::Format::B8G8R8A8_UNORM)));
+    }
+
+    #[test]
+    fn hdr_when_supported() {
+        let supported = [
+            (vk::Format::B8G8R8A8_SRGB,            SRGB_NONLINEAR),
+            (vk::Format::A2R10G10B10_UNORM_PACK32, vk::ColorSpaceKHR::HDR10_ST2084_EXT),
+            (vk::Format::R16G16B16A16_SFLOAT,      vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT)
+        ];
+
+        assert_eq!(choose(ColorSpace::Hdr10, &supported), Some(chosen(ColorSpace::Hdr10, vk::Format::A2R10G10B10_UNORM_PACK32)));
+        assert_eq!(choose(ColorSpace::ScRgb, &supported), Some(chosen(ColorSpace::ScRgb, vk::Format::R16G16B16A16_SFLOAT)));
+    }
+
+    #[test]
+    fn color_space_has_to_match() {
+        // NOTE: a known format, but in a color space it would be presented wrong in
+        let supported = [(vk::Format::R16G16B16A16_SFLOAT, SRGB_NONLINEAR), (vk::Format::B8G8R8A8_SRGB, vk::ColorSpaceKHR::DISPLAY_P3_NONLINEAR_EXT)];
+
+        assert_eq!(choose(ColorSpace::ScRgb, &supported), None);
+        assert_eq!(choose(ColorSpace::Srgb,  &supported), None);
+    }
+
+    #[test]
+    fn none_without_known_formats() {
+        let supported = [(vk::Format::R5G6B5_UNORM_PACK16, SRGB_NONLINEAR)];
+
+        for requested in [ColorSpace::Srgb, ColorSpace::SrgbUnorm, ColorSpace::Hdr10, ColorSpace::ScRgb] {
+            assert_eq!(choose(requested, &supported), None, "{requested:?}");
+            assert_eq!(choose(requested, &[]),        None, "{requested:?}");
+        }
+    }
+
+    #[test]
+    fn present_mode_when_supported() {
+        let supported = [vk::PresentModeKHR::FIFO, vk::PresentModeKHR::FIFO_RELAXED, vk::PresentModeKHR::MAILBOX, vk::PresentModeKHR::IMMEDIATE];
+
+        for requested in [PresentMode::Vsync, PresentMode::Mailbox, PresentMode::Immediate, PresentMode::FifoRelaxed] {
+            assert_eq!(requested.choose(&supported), requested);
+        }
+    }
+
+    #[test]
+    fn present_mode_fallbacks() {
+        let fifo_only = [vk::PresentModeKHR::FIFO];
+
+        for requested in [PresentMode::Vsync, PresentMode::Mailbox, PresentMode::Immediate, PresentMode::FifoRelaxed] {
+            assert_eq!(requested.choose(&fifo_only), PresentMode::Vsync, "{requested:?}");
+        }
+
+        assert_eq!(PresentMode::Immediate.choose(&[vk::PresentModeKHR::FIFO, vk::PresentModeKHR::MAILBOX]), PresentMode::Mailbox);
+        assert_eq!(PresentMode::Mailbox  .choose(&[vk::PresentModeKHR::FIFO, vk::PresentModeKHR::IMMEDIATE]), PresentMode::Vsync);
+    }
+
+    #[test]
+    fn present_mode_defaults_to_vsync() {
+        // NOTE: not a valid surface, but FIFO is assumed anyway
+        assert_eq!(PresentMode::Immediate.choose(&[]), PresentMode::Vsync);
+    }
+}
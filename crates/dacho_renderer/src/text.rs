@@ -0,0 +1,410 @@
+// dacho/crates/dacho_renderer/src/text.rs
+
+use std::{cmp::Reverse, collections::HashMap, fs, path::Path};
+
+use fontdue::FontSettings;
+
+use crate::mesh::{Index, Mesh, Quad};
+
+
+// what `Font::from_bytes` puts in the atlas
+pub const PRINTABLE_ASCII: &str = " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+// empty pixels around every glyph, so linear filtering does not bleed the neighbours in
+const PADDING: usize = 1;
+
+// the same quad as `Quad`, so text can be registered with its own pipeline,
+// like a `Space::Screen` one with alpha blending, registered last to be drawn on top
+pub struct GlyphQuad;
+impl Mesh for GlyphQuad {
+    type Vertex = [f32; 2];
+
+    fn vertices() -> impl IntoIterator<Item = Self::Vertex> {
+        Quad::vertices()
+    }
+
+    fn indices() -> impl IntoIterator<Item = Index> {
+        Quad::indices()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    // lines start at the origin
+    #[default]
+    Left,
+    // lines are centered on the origin
+    Center,
+    // lines end at the origin
+    Right
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TextStyle {
+    // height of a line, in the units of the pipeline space (world units or pixels)
+    pub size:         f32,
+    pub align:        Align,
+    // multiplies the distance between the lines
+    pub line_spacing: f32
+}
+
+impl TextStyle {
+    // NOTE: the rest of the fields can be changed with `..TextStyle::new(size)`
+    #[must_use]
+    pub const fn new(size: f32) -> Self {
+        Self {
+            size,
+            align:        Align::Left,
+            line_spacing: 1.0
+        }
+    }
+}
+
+// one laid out character, to be turned into an instance of `GlyphQuad`
+#[derive(Clone, Copy, Debug)]
+pub struct Glyph {
+    pub character: char,
+    // center of the quad, like the vertices of `Quad` are
+    pub position:  [f32; 2],
+    pub size:      [f32; 2],
+    // [u, v, width, height] of the glyph in the atlas
+    pub uv_rect:   [f32; 4]
+}
+
+// where a glyph is in the atlas, and how it sits on the baseline, in pixels at the rasterized size
+#[derive(Clone, Copy)]
+struct AtlasGlyph {
+    uv_rect: [f32; 4],
+    size:    [f32; 2],
+    // from the pen to the top left corner, +y down
+    offset:  [f32; 2],
+    advance: f32
+}
+
+// a TTF or OTF font, rasterized once into an atlas of the characters it will draw
+pub struct Font {
+    face:         fontdue::Font,
+    px:           f32,
+    ascent:       f32,
+    line_height:  f32,
+    glyphs:       HashMap<char, AtlasGlyph>,
+    atlas_width:  u32,
+    atlas_height: u32,
+    // RGBA8, white with the coverage in alpha
+    atlas_pixels: Vec<u8>
+}
+
+impl Font {
+    // `px` is the rasterized size in pixels per em, text drawn much larger than that gets blurry
+    //
+    /// # Errors
+    /// a message for the user, when `bytes` are not a TTF or OTF font
+    pub fn from_bytes(bytes: &[u8], px: f32) -> Result<Self, String> {
+        Self::from_bytes_with_chars(bytes, px, PRINTABLE_ASCII.chars())
+    }
+
+    /// # Errors
+    /// a message for the user, when the file can not be read as a TTF or OTF font
+    pub fn load(path: impl AsRef<Path>, px: f32) -> Result<Self, String> {
+        let file  = path.as_ref();
+        let bytes = fs::read(file)
+            .map_err(|err| format!("failed to read `{}`: {err}", file.display()))?;
+
+        Self::from_bytes(&bytes, px)
+            .map_err(|err| format!("`{}`: {err}", file.display()))
+    }
+
+    // like `Self::from_bytes`, with only `chars` in the atlas
+    //
+    /// # Errors
+    /// a message for the user, when `bytes` are not a TTF or OTF font
+    #[expect(clippy::panic_in_result_fn, reason = "a non-positive size is a bug, not a runtime failure")]
+    pub fn from_bytes_with_chars(bytes: &[u8], px: f32, chars: impl IntoIterator<Item = char>) -> Result<Self, String> {
+        assert!(px > 0.0, "fonts have to be rasterized at a positive size!");
+
+        let face = fontdue::Font::from_bytes(bytes, FontSettings { scale: px, ..FontSettings::default() })
+            .map_err(|err| format!("invalid font: {err}"))?;
+
+        let line_metrics = face
+            .horizontal_line_metrics(px)
+            .ok_or_else(|| String::from("invalid font: it has no horizontal line metrics"))?;
+
+        let mut rasterized = chars
+            .into_iter()
+            .filter(|&character| face.has_glyph(character))
+            .map(|character| {
+                let (metrics, coverage) = face.rasterize(character, px);
+
+                (character, metrics, coverage)
+            })
+            .collect::<Vec<_>>();
+
+        rasterized.sort_by_key(|&(character, ref metrics, _)| (Reverse(metrics.height), character));
+        rasterized.dedup_by_key(|&mut (character, _, _)| character);
+
+        let (atlas_width, atlas_height, positions) = pack(rasterized.iter().map(|glyph| [glyph.1.width, glyph.1.height]));
+
+        let mut atlas_pixels = vec![0_u8; atlas_width * atlas_height * 4];
+        let mut glyphs       = HashMap::with_capacity(rasterized.len());
+
+        for (&(character, ref metrics, ref coverage), [x, y]) in rasterized.iter().zip(positions) {
+            for (row, coverage_row) in coverage.chunks_exact(metrics.width.max(1)).enumerate() {
+                let start = ((y + row) * atlas_width + x) * 4;
+
+                for (pixel, &alpha) in atlas_pixels[start..start + metrics.width * 4].chunks_exact_mut(4).zip(coverage_row) {
+                    pixel.copy_from_slice(&[255, 255, 255, alpha]);
+                }
+            }
+
+            glyphs.insert(character, AtlasGlyph {
+                uv_rect: [
+                    x             as f32 / atlas_width  as f32,
+                    y             as f32 / atlas_height as f32,
+                    metrics.width  as f32 / atlas_width  as f32,
+                    metrics.height as f32 / atlas_height as f32
+                ],
+                size:    [metrics.width as f32, metrics.height as f32],
+                offset:  [metrics.xmin as f32, -(metrics.ymin as f32 + metrics.height as f32)],
+                advance: metrics.advance_width
+            });
+        }
+
+        Ok(Self {
+            face,
+            px,
+            ascent:       line_metrics.ascent,
+            line_height:  line_metrics.new_line_size,
+            glyphs,
+            atlas_width:  u32::try_from(atlas_width) .map_err(|err| format!("the font atlas is too large: {err}"))?,
+            atlas_height: u32::try_from(atlas_height).map_err(|err| format!("the font atlas is too large: {err}"))?,
+            atlas_pixels
+        })
+    }
+
+    // width, height and RGBA8 pixels, uploaded by `Meshes::add_font`
+    #[must_use]
+    pub(crate) fn atlas(&self) -> (u32, u32, Vec<u8>) {
+        (self.atlas_width, self.atlas_height, self.atlas_pixels.clone())
+    }
+
+    // `Self::layout`, with every glyph turned into an instance by `to_instance`
+    pub(crate) fn instances<I>(
+        &self,
+        text:        &str,
+        origin:      [f32; 2],
+        style:       &TextStyle,
+        to_instance: impl FnMut(Glyph) -> I
+    ) -> impl Iterator<Item = I> {
+        self.layout(text, origin, style).into_iter().map(to_instance)
+    }
+
+    // one `Glyph` per visible character, lines are split on '\n',
+    // `origin` is the top of the first line, +y down,
+    // characters that are not in the atlas are skipped
+    #[must_use]
+    pub fn layout(&self, text: &str, origin: [f32; 2], style: &TextStyle) -> Vec<Glyph> {
+        let scale = style.size / self.line_height;
+
+        let mut glyphs = Vec::with_capacity(text.len());
+
+        for (line_index, line_with_return) in text.split('\n').enumerate() {
+            let line = line_with_return.strip_suffix('\r').unwrap_or(line_with_return);
+
+            let baseline = (line_index as f32).mul_add(style.size * style.line_spacing, self.ascent.mul_add(scale, origin[1]));
+            let start    = glyphs.len();
+
+            let mut pen      = 0.0;
+            let mut previous = None;
+
+            for character in line.chars() {
+                let Some(atlas_glyph) = self.glyphs.get(&character) else {
+                    continue;
+                };
+
+                if let Some(previous_character) = previous {
+                    pen += self.face.horizontal_kern(previous_character, character, self.px).unwrap_or_default();
+                }
+
+                if atlas_glyph.size[0] != 0.0 && atlas_glyph.size[1] != 0.0 {
+                    glyphs.push(Glyph {
+                        character,
+                        position: [
+                            atlas_glyph.size[0].mul_add(0.5, pen + atlas_glyph.offset[0]) * scale,
+                            atlas_glyph.size[1].mul_add(0.5, atlas_glyph.offset[1]).mul_add(scale, baseline)
+                        ],
+                        size:     [atlas_glyph.size[0] * scale, atlas_glyph.size[1] * scale],
+                        uv_rect:  atlas_glyph.uv_rect
+                    });
+                }
+
+                pen      += atlas_glyph.advance;
+                previous  = Some(character);
+            }
+
+            let offset = match style.align {
+                Align::Left   => 0.0,
+                Align::Center => -0.5 * pen * scale,
+                Align::Right  => -pen * scale
+            };
+
+            for glyph in &mut glyphs[start..] {
+                glyph.position[0] += origin[0] + offset;
+            }
+        }
+
+        glyphs
+    }
+}
+
+// shelf packs rectangles (sorted by descending height) into a power of two wide atlas,
+// returns its width, height, and the top left corner of every rectangle
+fn pack(sizes: impl Iterator<Item = [usize; 2]> + Clone) -> (usize, usize, Vec<[usize; 2]>) {
+    let area = sizes
+        .clone()
+        .map(|[width, height]| (width + PADDING) * (height + PADDING))
+        .sum::<usize>();
+    let widest = sizes
+        .clone()
+        .map(|[width, _]| width + 2 * PADDING)
+        .max()
+        .unwrap_or(1);
+
+    let atlas_width = area.isqrt().max(widest).next_power_of_two();
+
+    let mut positions    = Vec::new();
+    let mut x            = PADDING;
+    let mut y            = PADDING;
+    let mut shelf_height = 0;
+
+    for [width, height] in sizes {
+        if x + width + PADDING > atlas_width {
+            x             = PADDING;
+            y            += shelf_height + PADDING;
+            shelf_height  = 0;
+        }
+
+        positions.push([x, y]);
+
+        x            += width + PADDING;
+        shelf_height  = shelf_height.max(height);
+    }
+
+    (atlas_width, y + shelf_height + PADDING, positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    // NOTE: 1000 units per em, 800 ascent, 200 descent, no line gap, so a line is exactly one em,
+    //       'A' and 'B' are 400x700 boxes 100 units right of the pen with a 600 advance,
+    //       'g' is the same box moved 200 units under the baseline, ' ' advances 300,
+    //       and "AB" is kerned by -100
+    const BOXES: &[u8] = include_bytes!("../tests/fonts/boxes.ttf");
+
+    // 10 pixels per em, so 1 pixel per 100 units
+    fn font() -> Font {
+        Font::from_bytes(BOXES, 10.0).unwrap()
+    }
+
+    fn assert_close(points: impl IntoIterator<Item = [f32; 2]>, expected: &[[f32; 2]]) {
+        let actual = points.into_iter().collect::<Vec<_>>();
+        let close  = |left: &[f32; 2], right: &[f32; 2]| (left[0] - right[0]).abs() < 1e-4 && (left[1] - right[1]).abs() < 1e-4;
+
+        assert!(
+            actual.len() == expected.len() && actual.iter().zip(expected).all(|(left, right)| close(left, right)),
+            "{actual:?} is not {expected:?}"
+        );
+    }
+
+    fn positions(glyphs: &[Glyph]) -> impl Iterator<Item = [f32; 2]> {
+        glyphs.iter().map(|glyph| glyph.position)
+    }
+
+    #[test]
+    fn pack_one_shelf() {
+        let (width, height, positions) = pack([[4, 7], [4, 7], [3, 5]].into_iter());
+
+        assert_eq!((width, height), (16, 9));
+        assert_eq!(positions,       [[1, 1], [6, 1], [11, 1]]);
+    }
+
+    #[test]
+    fn pack_wraps_to_the_next_shelf() {
+        let (width, height, positions) = pack([[6, 3], [6, 3], [6, 3]].into_iter());
+
+        assert_eq!((width, height), (16, 9));
+        assert_eq!(positions,       [[1, 1], [8, 1], [1, 5]]);
+    }
+
+    #[test]
+    fn pack_fits_the_widest() {
+        let (width, _, positions) = pack([[30, 2], [1, 1]].into_iter());
+
+        assert_eq!(width,     32);
+        assert_eq!(positions, [[1, 1], [1, 4]]);
+    }
+
+    #[test]
+    fn atlas_is_padded_and_power_of_two_wide() {
+        let (width, height, pixels) = font().atlas();
+
+        assert!(width.is_power_of_two());
+        assert_eq!(pixels.len(), width as usize * height as usize * 4);
+
+        // NOTE: the padding row and column stay empty
+        assert!(pixels[..width as usize * 4].iter().skip(3).step_by(4).all(|&alpha| alpha == 0));
+        assert!(pixels.chunks_exact(width as usize * 4).all(|row| row[3] == 0));
+    }
+
+    #[test]
+    fn layout_sits_on_the_baseline() {
+        let glyphs = font().layout("Ag", [0.0, 0.0], &TextStyle::new(10.0));
+
+        // NOTE: the baseline is the ascent under the origin
+        assert_close(positions(&glyphs), &[[3.0, 4.5], [9.0, 6.5]]);
+        assert_close([glyphs[0].size],   &[[4.0, 7.0]]);
+    }
+
+    #[test]
+    fn layout_scales_to_the_style_size() {
+        let glyphs = font().layout("A", [1.0, 2.0], &TextStyle::new(20.0));
+
+        assert_close(positions(&glyphs), &[[7.0, 11.0]]);
+        assert_close([glyphs[0].size],   &[[8.0, 14.0]]);
+    }
+
+    #[test]
+    fn layout_kerns_and_skips() {
+        let font = font();
+
+        // NOTE: B is pulled 1 pixel closer to A
+        assert_close(positions(&font.layout("AB",  [0.0, 0.0], &TextStyle::new(10.0))), &[[3.0, 4.5], [8.0, 4.5]]);
+        // NOTE: spaces only advance, and 'z' is not in the font
+        assert_close(positions(&font.layout("A A", [0.0, 0.0], &TextStyle::new(10.0))), &[[3.0, 4.5], [12.0, 4.5]]);
+        assert_close(positions(&font.layout("AzA", [0.0, 0.0], &TextStyle::new(10.0))), &[[3.0, 4.5], [9.0, 4.5]]);
+    }
+
+    #[test]
+    fn layout_aligns_every_line() {
+        let font  = font();
+        let style = |align| TextStyle { align, ..TextStyle::new(10.0) };
+
+        // NOTE: the lines are 12 and 6 pixels wide
+        assert_close(positions(&font.layout("AA\nA", [0.0, 0.0], &style(Align::Left))),   &[[ 3.0, 4.5], [ 9.0, 4.5], [ 3.0, 14.5]]);
+        assert_close(positions(&font.layout("AA\nA", [0.0, 0.0], &style(Align::Center))), &[[-3.0, 4.5], [ 3.0, 4.5], [ 0.0, 14.5]]);
+        assert_close(positions(&font.layout("AA\nA", [0.0, 0.0], &style(Align::Right))),  &[[-9.0, 4.5], [-3.0, 4.5], [-3.0, 14.5]]);
+    }
+
+    #[test]
+    fn layout_advances_lines() {
+        let font  = font();
+        let style = TextStyle { line_spacing: 1.5, ..TextStyle::new(10.0) };
+
+        assert_close(positions(&font.layout("A\nA",   [0.0, 0.0], &style)), &[[3.0, 4.5], [3.0, 19.5]]);
+        assert_close(positions(&font.layout("A\r\nA", [0.0, 0.0], &style)), &[[3.0, 4.5], [3.0, 19.5]]);
+        // NOTE: empty lines still take up space
+        assert_close(positions(&font.layout("A\n\nA", [0.0, 0.0], &style)), &[[3.0, 4.5], [3.0, 34.5]]);
+    }
+}
@@ -0,0 +1,56 @@
+// dacho/crates/dacho_renderer/src/texture.rs
+
+#[cfg(feature = "png")]
+use std::{fs, io, path::Path};
+
+
+// size of the bindless texture array in the fragment shader
+pub const MAX_TEXTURES: usize = 1024;
+
+// index into the bindless texture array, to be stored in instance data
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Texture(pub(crate) u32);
+
+impl Texture {
+    // 1x1 white pixel, so untextured instances can sample it too
+    pub const WHITE: Self = Self(0);
+
+    #[must_use]
+    pub const fn index(self) -> u32 {
+        self.0
+    }
+}
+
+pub(crate) struct TextureData {
+    pub(crate) width:  u32,
+    pub(crate) height: u32,
+    // RGBA8, emptied once uploaded
+    pub(crate) pixels: Vec<u8>
+}
+
+// reads a PNG file as tightly packed RGBA8 rows
+#[cfg(feature = "png")]
+#[must_use]
+pub fn read_png(path: &Path) -> Option<(u32, u32, Vec<u8>)> {
+    let file        = fs::File::open(path).ok()?;
+    let mut decoder = png::Decoder::new(io::BufReader::new(file));
+
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+    let mut reader = decoder.read_info().ok()?;
+    let mut pixels = vec![0; reader.output_buffer_size()?];
+    let info       = reader.next_frame(&mut pixels).ok()?;
+
+    pixels.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba           => pixels,
+        png::ColorType::Rgb            => pixels.chunks_exact(3).flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => pixels.chunks_exact(2).flat_map(|ga|  [ga[0],  ga[0],  ga[0],  ga[1]]).collect(),
+        png::ColorType::Grayscale      => pixels.iter().flat_map(|gray| [*gray, *gray, *gray, 255]).collect(),
+        // NOTE: expanded by the decoder
+        png::ColorType::Indexed        => return None
+    };
+
+    Some((info.width, info.height, rgba))
+}
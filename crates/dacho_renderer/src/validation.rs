@@ -0,0 +1,157 @@
+// dacho/crates/dacho_renderer/src/validation.rs
+
+use std::{env, ffi, ptr, sync::Mutex};
+
+use ash::{ext::debug_utils, vk};
+
+use crate::Error;
+
+
+// set to anything to enable validation, like `VulkanConfig::validation`
+pub const VALIDATION_ENV_VAR: &str = "DACHO_VALIDATION";
+
+const LAYER_NAME: &ffi::CStr = c"VK_LAYER_KHRONOS_validation";
+
+// NOTE: boxed, the callback gets a pointer to it
+struct State {
+    panic_on_error: bool,
+    first_error:    Mutex<Option<String>>
+}
+
+// forwards validation messages to `log`, with the `vulkan` target
+pub(crate) struct DebugMessenger {
+    ext_debug_utils: debug_utils::Instance,
+    messenger:       vk::DebugUtilsMessengerEXT,
+    state:           Box<State>
+}
+
+impl DebugMessenger {
+    // with validation if it was requested and the layer is installed,
+    // the messenger also covers instance creation and destruction
+    pub(crate) fn create_instance(
+        entry:               &ash::Entry,
+        application_info:    &vk::ApplicationInfo,
+        instance_extensions: &[*const ffi::c_char],
+        requested:           bool,
+        panic_on_error:      bool
+    ) -> Result<(ash::Instance, Option<Self>), Error> {
+        let layer_available = unsafe { entry.enumerate_instance_layer_properties() }
+            .map_err(Error::Instance)?
+            .iter()
+            .any(|layer| layer.layer_name_as_c_str() == Ok(LAYER_NAME));
+
+        let enabled = requested || env::var_os(VALIDATION_ENV_VAR).is_some();
+
+        if !(enabled && layer_available) {
+            if enabled {
+                log::warn!(target: "vulkan", "validation was requested, but `{}` is not installed", LAYER_NAME.to_string_lossy());
+            }
+
+            let instance_create_info = vk::InstanceCreateInfo::default()
+                .enabled_extension_names(instance_extensions)
+                .application_info(application_info);
+            let instance = unsafe { entry.create_instance(&instance_create_info, None) }
+                .map_err(Error::Instance)?;
+
+            return Ok((instance, None));
+        }
+
+        let state = Box::new(State { panic_on_error, first_error: Mutex::new(None) });
+
+        let mut extension_names = instance_extensions.to_vec();
+        extension_names.push(debug_utils::NAME.as_ptr());
+
+        let layer_names = [LAYER_NAME.as_ptr()];
+
+        let mut instance_messenger_create_info = messenger_create_info(&state);
+        let instance_create_info = vk::InstanceCreateInfo::default()
+            .enabled_extension_names(&extension_names)
+            .enabled_layer_names(&layer_names)
+            .application_info(application_info)
+            .push_next(&mut instance_messenger_create_info);
+        let instance = unsafe { entry.create_instance(&instance_create_info, None) }
+            .map_err(Error::Instance)?;
+
+        let ext_debug_utils = debug_utils::Instance::new(entry, &instance);
+        let messenger       = unsafe { ext_debug_utils.create_debug_utils_messenger(&messenger_create_info(&state), None) }
+            .inspect_err(|_| unsafe { instance.destroy_instance(None); })
+            .map_err(Error::Instance)?;
+
+        Ok((instance, Some(Self { ext_debug_utils, messenger, state })))
+    }
+
+    // NOTE: called after every frame, the callback itself can not unwind into the driver
+    pub(crate) fn check(&self) {
+        if !self.state.panic_on_error {
+            return;
+        }
+
+        let first_error = self.state.first_error
+            .lock()
+            .unwrap()
+            .take();
+
+        assert!(first_error.is_none(), "vulkan validation error: {}", first_error.unwrap_or_default());
+    }
+
+    pub(crate) fn destroy(&self) {
+        unsafe { self.ext_debug_utils.destroy_debug_utils_messenger(self.messenger, None); }
+    }
+}
+
+fn messenger_create_info(state: &State) -> vk::DebugUtilsMessengerCreateInfoEXT<'static> {
+    vk::DebugUtilsMessengerCreateInfoEXT::default()
+        .message_severity(
+            vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE |
+            vk::DebugUtilsMessageSeverityFlagsEXT::INFO    |
+            vk::DebugUtilsMessageSeverityFlagsEXT::WARNING |
+            vk::DebugUtilsMessageSeverityFlagsEXT::ERROR
+        )
+        .message_type(
+            vk::DebugUtilsMessageTypeFlagsEXT::GENERAL     |
+            vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION  |
+            vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
+        )
+        .pfn_user_callback(Some(callback))
+        .user_data(ptr::from_ref(state).cast_mut().cast())
+}
+
+unsafe extern "system" fn callback(
+    severity:      vk::DebugUtilsMessageSeverityFlagsEXT,
+    message_type:  vk::DebugUtilsMessageTypeFlagsEXT,
+    callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT<'_>,
+    user_data:     *mut ffi::c_void
+) -> vk::Bool32 {
+    let level = match severity {
+        vk::DebugUtilsMessageSeverityFlagsEXT::ERROR   => log::Level::Error,
+        vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => log::Level::Warn,
+        vk::DebugUtilsMessageSeverityFlagsEXT::INFO    => log::Level::Info,
+        _                                              => log::Level::Trace
+    };
+
+    let kind = match message_type {
+        vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION  => "validation",
+        vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE => "performance",
+        _                                              => "general"
+    };
+
+    let message = unsafe { callback_data.as_ref() }
+        .and_then(|data| unsafe { data.message_as_c_str() })
+        .map_or_else(String::new, |message| message.to_string_lossy().into_owned());
+
+    log::log!(target: "vulkan", level, "[{kind}] {message}");
+
+    if level == log::Level::Error {
+        let user_state = unsafe { user_data.cast::<State>().as_ref() };
+
+        // NOTE: no unwrapping, panics can not unwind out of here
+        if let Some(state) = user_state.filter(|candidate| candidate.panic_on_error)
+            && let Ok(mut first_error) = state.first_error.lock()
+        {
+            first_error.get_or_insert(message);
+        }
+    }
+
+    // NOTE: the call that triggered the message is not aborted
+    vk::FALSE
+}
@@ -0,0 +1,152 @@
+// dacho/crates/dacho_renderer/tests/snapshot.rs
+
+#[cfg(test)]
+mod tests {
+    use dacho_renderer::Meshes;
+    use dacho_renderer::compute::{Buffer, ComputePassDescription, Dispatch, PushConstants};
+    use dacho_renderer::mesh::{Circle, Instance, Quad};
+    use dacho_renderer::pipeline::{Blend, PipelineDescription, ShaderSource};
+    use dacho_renderer::snapshot::{assert_snapshot, compare, SnapshotConfig};
+
+
+    // NOTE: has to match `Instance` in the example shaders
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    struct Sprite {
+        position: [f32; 2],
+        rotation: f32,
+        scale:    [f32; 2],
+        color:    [f32; 4],
+        uv_rect:  [f32; 4],
+        texture:  u32
+    }
+
+    // SAFETY: plain old data
+    unsafe impl Instance for Sprite {}
+
+    // NOTE: has to match the push constants in the example comp.glsl
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Spin {
+        angle: f32
+    }
+
+    // SAFETY: plain old data
+    unsafe impl PushConstants for Spin {}
+
+    impl Sprite {
+        const fn new(x: f32, y: f32, scale: f32) -> Self {
+            Self { position: [x, y], rotation: 0.0, scale: [scale, scale], color: [1.0, 1.0, 1.0, 1.0], uv_rect: [0.0, 0.0, 1.0, 1.0], texture: 0 }
+        }
+    }
+
+    #[test]
+    fn quads_and_circles() {
+        let mut meshes = Meshes::<Sprite>::default();
+
+        let pipeline = meshes.add_pipeline(PipelineDescription::new(
+            ShaderSource::glsl(concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/usage/assets/shaders/test/vert.glsl")),
+            ShaderSource::glsl(concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/usage/assets/shaders/test/frag.glsl"))
+        ));
+        // NOTE: only spins the quads, dispatched once per frame
+        let spin = meshes.add_compute_pass(ComputePassDescription {
+            shader:   ShaderSource::glsl(concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/usage/assets/shaders/test/comp.glsl")),
+            dispatch: Dispatch::instances::<Quad>(),
+            writes:   vec![Buffer::Instances]
+        });
+        meshes.set_push_constants(spin, &Spin { angle: 0.1 });
+
+        // NOTE: two different meshes, so the second one is drawn with non-zero
+        //       vertex and index offsets (gl_BaseVertex and index_offset in vert.glsl)
+        meshes.register::<Quad>  (pipeline, 4);
+        meshes.register::<Circle>(pipeline, 2);
+
+        for x in [-0.75, -0.25, 0.25, 0.75] {
+            meshes.add_instance::<Quad>(Sprite::new(x, -0.5, 0.1));
+        }
+        for x in [-0.5, 0.5] {
+            meshes.add_instance::<Circle>(Sprite::new(x, 0.5, 1.0));
+        }
+
+        assert_snapshot(
+            "quads_and_circles",
+            meshes,
+            &SnapshotConfig {
+                frames: 3,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn alpha_over_opaque() {
+        let mut meshes = Meshes::<Sprite>::default();
+
+        let vertex   = ShaderSource::glsl(concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/usage/assets/shaders/test/vert.glsl"));
+        let fragment = ShaderSource::glsl(concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/usage/assets/shaders/test/frag.glsl"));
+
+        let opaque = meshes.add_pipeline(PipelineDescription::new(vertex.clone(), fragment.clone()));
+        let alpha  = meshes.add_pipeline(PipelineDescription {
+            blend: Blend::Alpha,
+            ..PipelineDescription::new(vertex, fragment)
+        });
+
+        // NOTE: drawn in this order, so the half transparent red circle has to end up on top
+        meshes.register::<Quad>  (opaque, 1);
+        meshes.register::<Circle>(alpha,  1);
+
+        meshes.add_instance::<Quad>  (Sprite::new(-0.25, -0.25, 1.0));
+        meshes.add_instance::<Circle>(Sprite { color: [1.0, 0.0, 0.0, 0.5], ..Sprite::new(0.25, 0.25, 1.0) });
+
+        assert_snapshot("alpha_over_opaque", meshes, &SnapshotConfig::default());
+    }
+
+    #[test]
+    fn compare_identical() {
+        let image = [10, 20, 30, 255, 40, 50, 60, 255];
+
+        let comparison = compare(&image, &image, 0);
+
+        assert_eq!(comparison.mismatched_pixels, 0);
+        assert_eq!(comparison.max_difference,    0);
+    }
+
+    #[test]
+    fn compare_tolerance_edges() {
+        let expected = [100, 100, 100, 255, 100, 100, 100, 255];
+        // NOTE: the first pixel is exactly at the tolerance, the second one just past it
+        let actual   = [102, 100,  98, 255, 100, 103, 100, 255];
+
+        let comparison = compare(&expected, &actual, 2);
+
+        assert_eq!(comparison.mismatched_pixels, 1);
+        assert_eq!(comparison.max_difference,    3);
+
+        assert_eq!(compare(&expected, &actual, 3).mismatched_pixels, 0);
+    }
+
+    #[test]
+    fn compare_counts_alpha() {
+        let comparison = compare(&[0, 0, 0, 255], &[0, 0, 0, 0], 254);
+
+        assert_eq!(comparison.mismatched_pixels, 1);
+        assert_eq!(comparison.max_difference,    255);
+    }
+
+    #[test]
+    fn compare_diff_output() {
+        let expected = [200, 100,   0, 255,  0, 0, 0, 255];
+        let actual   = [200, 100,   0, 255, 50, 0, 0, 255];
+
+        let comparison = compare(&expected, &actual, 2);
+
+        // NOTE: matching pixels are the expected luminance faded to a quarter, mismatched ones are red
+        assert_eq!(comparison.diff, [25, 25, 25, 255, 255, 0, 0, 255]);
+    }
+
+    #[test]
+    #[should_panic = "compared images have different sizes"]
+    fn compare_size_mismatch() {
+        let _comparison = compare(&[0; 8], &[0; 4], 0);
+    }
+}
@@ -1,12 +1,8 @@
 // dacho/crates/dacho_window/src/lib.rs
 
-use std::ffi;
-
-use ash_window::enumerate_required_extensions;
-
 use winit::dpi::PhysicalSize;
+use winit::error::OsError;
 use winit::event_loop::ActiveEventLoop;
-use winit::raw_window_handle::HasDisplayHandle as _;
 use winit::window::Window as Handle;
 
 pub use winit;
@@ -21,23 +17,26 @@ pub struct Window {
 }
 
 impl Window {
-    pub fn initialise(&mut self, event_loop: &ActiveEventLoop) {
+    /// # Errors
+    /// when the window can not be created, it can be tried again then
+    pub fn initialise(&mut self, event_loop: &ActiveEventLoop) -> Result<(), OsError> {
         if self.initialised {
-            return;
+            return Ok(());
         }
-        self.initialised = true;
 
         let window_attributes = Handle::default_attributes()
             .with_inner_size(PhysicalSize::<u16> { width: 1500, height: 1000 })
             .with_resizable(false)
             .with_title("dacho");
         let window = event_loop
-            .create_window(window_attributes)
-            .unwrap();
+            .create_window(window_attributes)?;
         let inner_size = window.inner_size();
 
-        self.handle = Some(window);
-        self.size   = (inner_size.width, inner_size.height).into();
+        self.initialised = true;
+        self.handle      = Some(window);
+        self.size        = (inner_size.width, inner_size.height).into();
+
+        Ok(())
     }
 
     #[must_use]
@@ -45,17 +44,6 @@ impl Window {
         self.handle.as_ref().unwrap()
     }
 
-    #[must_use]
-    pub fn required_extensions(&self, event_loop: &ActiveEventLoop) -> &'static [*const ffi::c_char] {
-        let display_handle = event_loop
-            .display_handle()
-            .unwrap()
-            .into();
-
-        enumerate_required_extensions(display_handle)
-            .unwrap()
-    }
-
     #[must_use]
     #[inline]
     pub fn resized(&mut self, new_size: PhysicalSize<u32>) -> bool {
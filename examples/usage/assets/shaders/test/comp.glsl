@@ -11,7 +11,12 @@
 layout(local_size_x = 128, local_size_y = 1, local_size_z = 1) in;
 
 struct Instance {
-    vec2 position;
+    vec2  position;
+    float rotation;
+    vec2  scale;
+    vec4  color;
+    vec4  uv_rect;
+    uint  texture;
 };
 
 layout(buffer_reference, scalar) buffer InstanceBuffer {
@@ -22,13 +27,20 @@ layout(push_constant) uniform PushConstant {
     uint64_t  vertices_pointer;
     uint64_t   indices_pointer;
     uint64_t instances_pointer;
-    uint32_t      index_offset;
+    uint32_t            offset;
+    uint32_t             count;
+    // `Spin` in main.rs
+    float                angle;
 } pc;
 
 
 void main() {
+    if (gl_GlobalInvocationID.x >= pc.count) {
+        return;
+    }
+
     InstanceBuffer instance_buffer = InstanceBuffer(pc.instances_pointer);
 
-    instance_buffer.data[gl_GlobalInvocationID.x].position += vec2(0.0005);
+    instance_buffer.data[pc.offset + gl_GlobalInvocationID.x].rotation += pc.angle;
 }
 
@@ -2,11 +2,16 @@
 
 #version 460
 
+#extension GL_EXT_nonuniform_qualifier : require
 
-layout(location = 0) in  vec4  in_color;
-layout(location = 0) out vec4 out_color;
+
+layout(set = 0, binding = 0) uniform sampler2D textures[];
+
+layout(location = 0)      in  vec4 in_color;
+layout(location = 1)      in  vec2 in_uv;
+layout(location = 2) flat in  uint in_texture;
+layout(location = 0)      out vec4 out_color;
 
 void main() {
-    out_color = in_color;
+    out_color = in_color * texture(textures[nonuniformEXT(in_texture)], in_uv);
 }
-
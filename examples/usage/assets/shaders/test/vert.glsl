@@ -13,7 +13,12 @@ struct Vertex {
 };
 
 struct Instance {
-    vec2 position;
+    vec2  position;
+    float rotation;
+    vec2  scale;
+    vec4  color;
+    vec4  uv_rect;
+    uint  texture;
 };
 
 layout(buffer_reference, scalar) buffer   VertexBuffer {
@@ -33,16 +38,13 @@ layout(push_constant) uniform PushConstant {
     uint64_t   indices_pointer;
     uint64_t instances_pointer;
     uint32_t      index_offset;
+    mat4       view_projection;
 } pc;
 
-layout(location = 0) out vec3 out_color;
-
+layout(location = 0)      out vec4 out_color;
+layout(location = 1)      out vec2 out_uv;
+layout(location = 2) flat out uint out_texture;
 
-const vec3 colors[3] = vec3[](
-    vec3(1.0, 0.0, 0.0),
-    vec3(0.0, 1.0, 0.0),
-    vec3(0.0, 0.0, 1.0)
-);
 
 void main() {
     VertexBuffer     vertex_buffer =   VertexBuffer(pc. vertices_pointer);
@@ -55,9 +57,14 @@ void main() {
     Vertex   vertex       =   vertex_buffer.data[gl_BaseVertex   +        vertex_index];
     Instance instance     = instance_buffer.data[gl_InstanceIndex];
 
-    vec2 position = vertex.position + instance.position;
+    float s       = sin(instance.rotation);
+    float c       = cos(instance.rotation);
+    vec2  scaled  = vertex.position * instance.scale;
+    vec2  rotated = vec2(c * scaled.x - s * scaled.y, s * scaled.x + c * scaled.y);
 
-    gl_Position = vec4(position, 0.0, 1.0);
-    out_color   = colors[object_vertex_index % 3];
+    gl_Position = pc.view_projection * vec4(rotated + instance.position, 0.0, 1.0);
+    out_color   = instance.color;
+    out_uv      = instance.uv_rect.xy + (vertex.position + 0.5) * instance.uv_rect.zw;
+    out_texture = instance.texture;
 }
 
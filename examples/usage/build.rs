@@ -0,0 +1,5 @@
+// dacho/examples/usage/build.rs
+
+fn main() {
+    dacho_renderer::glsl::compile_shaders("assets/shaders");
+}
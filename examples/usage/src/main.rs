@@ -1,20 +1,50 @@
 // dacho/examples/usage/src/main.rs
 
-use dacho::app::{App, GameTrait};
-use dacho::renderer::{Meshes, MeshesCapacities};
-use dacho::renderer::mesh::{Quad, VERTEX_SIZE, INDEX_SIZE, INSTANCE_SIZE};
+use dacho::app::{App, Error, GameTrait};
+use dacho::renderer::{include_shader, Meshes, MeshesCapacities, Renderer};
+use dacho::renderer::compute::{Buffer, ComputePass, ComputePassDescription, Dispatch, PushConstants};
+use dacho::renderer::mesh::{Instance, Mesh, Quad, INDEX_SIZE};
+use dacho::renderer::pipeline::PipelineDescription;
 
 
-fn main() {
-    App::<Game>::default()
-        .run();
+fn main() -> Result<(), Error> {
+    App::<Game, Sprite>::default()
+        .run()
 }
 
+// NOTE: has to match `Instance` in the shaders
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct Sprite {
+    position: [f32; 2],
+    rotation: f32,
+    scale:    [f32; 2],
+    color:    [f32; 4],
+    uv_rect:  [f32; 4],
+    texture:  u32
+}
+
+// SAFETY: plain old data
+unsafe impl Instance for Sprite {}
+
+// NOTE: has to match the push constants in comp.glsl, after the header
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Spin {
+    angle: f32
+}
+
+// SAFETY: plain old data
+unsafe impl PushConstants for Spin {}
+
 #[derive(Default)]
-struct Game;
+struct Game {
+    time: f32,
+    spin: Option<ComputePass>
+}
 
-impl GameTrait for Game {
-    fn setup(&mut self) -> Meshes {
+impl GameTrait<Sprite> for Game {
+    fn setup(&mut self) -> Meshes<Sprite> {
         let per_w = 16;
         let per_h =  8;
         let count = per_w * per_h;
@@ -22,24 +52,65 @@ impl GameTrait for Game {
         let mut meshes = Meshes::with_capacities(
             &MeshesCapacities {
                 different_meshes_count: 1,
-                vertex_buffer_size:     4     *   VERTEX_SIZE,
-                index_buffer_size:      6     *    INDEX_SIZE,
-                instance_buffer_size:   count * INSTANCE_SIZE
+                vertex_buffer_size:     4 * size_of::<<Quad as Mesh>::Vertex>(),
+                index_buffer_size:      6 * INDEX_SIZE,
+                instance_buffer_size:   count
             }
         );
 
-        meshes.register::<Quad>(count);
+        let pipeline = meshes.add_pipeline(PipelineDescription::new(
+            include_shader!("test/vert.glsl"),
+            include_shader!("test/frag.glsl")
+        ));
+
+        meshes.register::<Quad>(pipeline, count);
+
+        self.spin = Some(meshes.add_compute_pass(ComputePassDescription {
+            shader:   include_shader!("test/comp.glsl"),
+            dispatch: Dispatch::instances::<Quad>(),
+            writes:   vec![Buffer::Instances]
+        }));
+
+        let checkerboard = meshes.add_texture(8, 8, checkerboard(8));
 
         for y in 0..per_h {
             for x in 0..per_w {
-                let x = (x as f32 / (per_w - 1) as f32 - 0.5) * 1.7;
-                let y =  y as f32 / (per_h - 1) as f32 - 0.5;
+                let u = x as f32 / (per_w - 1) as f32;
+                let v = y as f32 / (per_h - 1) as f32;
 
-                meshes.add_instance::<Quad>([x, y]);
+                meshes.add_instance::<Quad>(Sprite {
+                    position: [(u - 0.5) * 1.7, v - 0.5],
+                    rotation: u * std::f32::consts::PI,
+                    scale:    [0.1, 0.1],
+                    color:    [u, v, 1.0 - u, 1.0],
+                    uv_rect:  [0.0, 0.0, 1.0, 1.0],
+                    texture:  checkerboard.index()
+                });
             }
         }
 
         meshes
     }
+
+    fn update(&mut self, renderer: &mut Renderer<Sprite>, delta_time: f32) {
+        self.time += delta_time;
+
+        renderer.camera.zoom     = 1.0 + 0.25 * self.time.sin();
+        renderer.camera.position = [0.1 * (0.5 * self.time).cos(), 0.0];
+
+        if let Some(spin) = self.spin {
+            renderer.set_push_constants(spin, &Spin { angle: delta_time });
+        }
+    }
 }
 
+// RGBA8 pixels of a black and white checkerboard
+fn checkerboard(size: u32) -> Vec<u8> {
+    (0..size * size)
+        .flat_map(|i| {
+            let value = if (i % size + i / size).is_multiple_of(2) { 255 } else { 64 };
+
+            [value, value, value, 255]
+        })
+        .collect()
+}
@@ -62,14 +62,12 @@
         defaultShellMessage = ''
           echo -e "\n  \x1b[100m          \x1b[1mwelcome to the \x1b[32mdefault dacho shell\x1b[39m!\x1b[22m           \x1b[0m"
           echo -e "  \x1b[40;90m▎\x1b[39mbuild the example\x1b[30;100m▊\x1b[0;40;1m cargo build --release              \x1b[30;100m▊\x1b[0m"
-          echo -e "  \x1b[40;90m▎\x1b[39mcompile shaders  \x1b[30;100m▊\x1b[0;40;1m ./examples/usage/compile_shaders.sh\x1b[30;100m▊\x1b[0m"
           echo -e "  \x1b[40;90m▎\x1b[39mrun the example  \x1b[30;100m▊\x1b[0;40;1m ./target/release/usage             \x1b[30;100m▊\x1b[0m"
           echo -e "  \x1b[90m▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔\x1b[0m"
         '';
         debugShellMessage = ''
           echo -e "\n  \x1b[100m           \x1b[1mwelcome to the \x1b[33mdebug dacho shell\x1b[39m!\x1b[22m            \x1b[0m"
           echo -e "  \x1b[40;90m▎\x1b[39mbuild the example\x1b[30;100m▊\x1b[0;40;1m cargo build                        \x1b[30;100m▊\x1b[0m"
-          echo -e "  \x1b[40;90m▎\x1b[39mcompile shaders  \x1b[30;100m▊\x1b[0;40;1m ./examples/usage/compile_shaders.sh\x1b[30;100m▊\x1b[0m"
           echo -e "  \x1b[40;90m▎\x1b[39mprepare vk layers\x1b[30;100m▊\x1b[0;40;1m vkconfig-gui &                     \x1b[30;100m▊\x1b[0m"
           echo -e "  \x1b[40;90m▎\x1b[39mdebug graphics   \x1b[30;100m▊\x1b[0;40;1m qrenderdoc                         \x1b[30;100m▊\x1b[0m"
           echo -e "  \x1b[90m▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔\x1b[0m"